@@ -0,0 +1,71 @@
+use aoclib::parsers::{find_byte, parse_signed, parse_unsigned, try_parse_num};
+use divan::Bencher;
+
+fn main() {
+    // Run registered benchmarks.
+    divan::main();
+}
+
+const DIGITS: &[u32] = &[2, 4, 8, 12, 18];
+
+/// Build a space delimited list of numbers with between 1 and `digits` digits each.
+///
+/// Widths are mixed, like real puzzle inputs, so the byte-at-a-time loop can't just learn the
+/// length of every number through the branch predictor.
+fn numbers(digits: u32) -> Vec<u8> {
+    // xorshift so the input is the same every run
+    let mut state = 0x2545_F491_4F6C_DD1D_u64;
+    (0..10_000)
+        .flat_map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let width = 1 + (state % digits as u64) as u32;
+            format!("{} ", state % 10_u64.pow(width)).into_bytes()
+        })
+        .collect()
+}
+
+fn sum_with(input: &[u8], parse: impl Fn(&[u8]) -> Option<(u64, usize)>) -> u64 {
+    let mut offset = 0;
+    let mut sum = 0_u64;
+    while let Some((num, bytes_read)) = parse(&input[offset..]) {
+        sum = sum.wrapping_add(num);
+        offset += bytes_read + 1;
+    }
+    sum
+}
+
+#[divan::bench(args = DIGITS)]
+fn try_parse_num_u64(bencher: Bencher, digits: u32) {
+    let input = numbers(digits);
+    bencher.bench(|| sum_with(divan::black_box(&input), try_parse_num::<u64>));
+}
+
+#[divan::bench(args = DIGITS)]
+fn parse_unsigned_u64(bencher: Bencher, digits: u32) {
+    let input = numbers(digits);
+    bencher.bench(|| sum_with(divan::black_box(&input), parse_unsigned::<u64>));
+}
+
+#[divan::bench(args = DIGITS)]
+fn parse_signed_i64(bencher: Bencher, digits: u32) {
+    let input = numbers(digits);
+    bencher.bench(|| {
+        sum_with(divan::black_box(&input), |bytes| {
+            parse_signed::<i64>(bytes).map(|(num, bytes_read)| (num as u64, bytes_read))
+        })
+    });
+}
+
+#[divan::bench]
+fn find_newline_bytewise(bencher: Bencher) {
+    let input = numbers(18);
+    bencher.bench(|| divan::black_box(&input).iter().position(|b| *b == b'\n'));
+}
+
+#[divan::bench]
+fn find_newline_memchr(bencher: Bencher) {
+    let input = numbers(18);
+    bencher.bench(|| find_byte(b'\n', divan::black_box(&input)));
+}
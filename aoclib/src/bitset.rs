@@ -0,0 +1,156 @@
+/// A set of small integers, one bit each, that grows to fit whatever is inserted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Room for `0..bits` without growing.
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    /// Add `bit`, returning whether it was newly added.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let (word, mask) = (bit / 64, 1 << (bit % 64));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let added = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        added
+    }
+
+    /// Remove `bit`, returning whether it was there.
+    pub fn remove(&mut self, bit: usize) -> bool {
+        let Some(word) = self.words.get_mut(bit / 64) else {
+            return false;
+        };
+        let mask = 1 << (bit % 64);
+        let removed = *word & mask != 0;
+        *word &= !mask;
+        removed
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        self.words
+            .get(bit / 64)
+            .is_some_and(|word| word & (1 << (bit % 64)) != 0)
+    }
+
+    /// Add everything in `other`.
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Whether anything is in both sets.
+    pub fn intersects(&self, other: &BitSet) -> bool {
+        self.words.iter().zip(&other.words).any(|(a, b)| a & b != 0)
+    }
+
+    /// Everything in both sets, smallest first.
+    pub fn intersection<'a>(&'a self, other: &'a BitSet) -> impl Iterator<Item = usize> + 'a {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .enumerate()
+            .flat_map(|(i, (a, b))| ones(i, a & b))
+    }
+
+    /// Everything in the set, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(i, word)| ones(i, *word))
+    }
+
+    /// Number of bits set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Empty the set, keeping its capacity.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for bit in iter {
+            set.insert(bit);
+        }
+        set
+    }
+}
+
+/// The set bits of the `i`th word.
+fn ones(i: usize, mut word: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if word == 0 {
+            return None;
+        }
+        let bit = word.trailing_zeros() as usize;
+        word &= word - 1;
+        Some(i * 64 + bit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set = BitSet::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(200));
+        assert!(set.contains(3) && set.contains(200));
+        assert!(!set.contains(4) && !set.contains(10_000));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.remove(200));
+        assert!(!set.remove(200));
+        assert!(!set.remove(10_000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3]);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: BitSet = [1, 64, 65, 300].into_iter().collect();
+        let mut b: BitSet = [0, 65, 130].into_iter().collect();
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b).collect::<Vec<_>>(), vec![65]);
+
+        b.union_with(&a);
+        assert_eq!(b.iter().collect::<Vec<_>>(), vec![0, 1, 64, 65, 130, 300]);
+
+        let c: BitSet = [2, 400].into_iter().collect();
+        assert!(!a.intersects(&c));
+        assert_eq!(a.intersection(&c).count(), 0);
+    }
+}
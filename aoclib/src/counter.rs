@@ -0,0 +1,181 @@
+use std::hash::Hash;
+
+use rustc_hash::{FxBuildHasher, FxHashMap};
+
+/// A multiset, counting how many times each item was added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: FxHashMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Self {
+        Self {
+            counts: FxHashMap::default(),
+        }
+    }
+
+    /// Room for `capacity` distinct items.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            counts: FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher),
+        }
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    /// Add `item` `n` times. Adding it zero times leaves no entry for it.
+    pub fn add_n(&mut self, item: T, n: usize) {
+        if n == 0 {
+            return;
+        }
+        *self.counts.entry(item).or_insert(0) += n;
+    }
+
+    /// How many times `item` was added, 0 if never.
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Add every count in `other` to this one.
+    pub fn merge(&mut self, other: Counter<T>) {
+        for (item, n) in other {
+            self.add_n(item, n);
+        }
+    }
+
+    /// Each distinct item and its count, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, n)| (item, *n))
+    }
+
+    /// Every item by count, highest first, with ties broken by the smaller item.
+    pub fn most_common(&self) -> Vec<(&T, usize)>
+    where
+        T: Ord,
+    {
+        let mut items: Vec<_> = self.iter().collect();
+        items.sort_unstable_by(|(a, a_n), (b, b_n)| b_n.cmp(a_n).then_with(|| a.cmp(b)));
+        items
+    }
+
+    /// Sum of all the counts.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Hash + Eq> IntoIterator for Counter<T> {
+    type Item = (T, usize);
+    type IntoIter = std::collections::hash_map::IntoIter<T, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+/// Collect `items` into groups that share the same `key`, keeping their order within a group.
+pub fn group_by_key<T, K: Hash + Eq>(
+    items: impl IntoIterator<Item = T>,
+    mut key: impl FnMut(&T) -> K,
+) -> FxHashMap<K, Vec<T>> {
+    let mut groups: FxHashMap<K, Vec<T>> = FxHashMap::default();
+    for item in items {
+        groups.entry(key(&item)).or_default().push(item);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let mut counter: Counter<u8> = b"abracadabra".iter().copied().collect();
+        assert_eq!(counter.get(&b'a'), 5);
+        assert_eq!(counter.get(&b'z'), 0);
+        assert_eq!(counter.len(), 5);
+        assert_eq!(counter.total(), 11);
+
+        counter.add_n(b'z', 3);
+        counter.add(b'c');
+        assert_eq!(
+            counter.most_common(),
+            vec![
+                (&b'a', 5),
+                (&b'z', 3),
+                (&b'b', 2),
+                (&b'c', 2),
+                (&b'r', 2),
+                (&b'd', 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_zero() {
+        let mut counter = Counter::new();
+        counter.add_n(b'a', 0);
+        assert!(counter.is_empty());
+        assert_eq!(counter.most_common(), vec![]);
+
+        counter.add_n(b'a', 2);
+        counter.add_n(b'a', 0);
+        assert_eq!(counter.get(&b'a'), 2);
+        assert_eq!(counter.len(), 1);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut counter: Counter<&str> = ["x", "y"].into_iter().collect();
+        counter.merge(["y", "z", "z"].into_iter().collect());
+        assert_eq!(counter.get(&"x"), 1);
+        assert_eq!(counter.get(&"y"), 2);
+        assert_eq!(counter.get(&"z"), 2);
+        assert_eq!(counter.total(), 5);
+        assert!(Counter::<u8>::default().is_empty());
+    }
+
+    #[test]
+    fn test_group_by_key() {
+        let groups = group_by_key(1..=10, |n| n % 3);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&0], vec![3, 6, 9]);
+        assert_eq!(groups[&1], vec![1, 4, 7, 10]);
+        assert_eq!(groups[&2], vec![2, 5, 8]);
+    }
+}
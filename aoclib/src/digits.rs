@@ -0,0 +1,225 @@
+//! Exact decimal digit utilities for the unsigned integer types.
+//!
+//! Everything here is integer only, so there is no `f64` rounding near large powers of ten, and
+//! `0` has one digit.
+
+/// Decimal digit operations, implemented for every unsigned integer width.
+pub trait Digits: Copy + Sized {
+    /// The number of decimal digits in `self`, where `0` has one digit.
+    fn count_digits(self) -> u32;
+
+    /// `10^exp`, or None if it doesn't fit in `Self`.
+    fn checked_pow10(exp: u32) -> Option<Self>;
+
+    /// Split off the lowest `n` digits, so `1234.split_digits_at(1) == (123, 4)`.
+    ///
+    /// If `n` is at least the number of digits the high half is `0`.
+    fn split_digits_at(self, n: u32) -> (Self, Self);
+
+    /// Append the digits of `other` to `self`, so `12.checked_concat(345) == Some(12345)`.
+    ///
+    /// Returns None if the result doesn't fit in `Self`.
+    fn checked_concat(self, other: Self) -> Option<Self>;
+
+    /// Same as [`Digits::checked_concat`], but wraps around on overflow.
+    fn wrapping_concat(self, other: Self) -> Self;
+
+    /// Iterate over the digits, most significant first.
+    fn digits(self) -> DigitsIter<Self>;
+
+    /// Reverse the digits, dropping what become leading zeros, so `1200` becomes `21`.
+    ///
+    /// Returns None if the result doesn't fit in `Self`.
+    fn checked_reverse_digits(self) -> Option<Self>;
+}
+
+/// The number of decimal digits in `n`, where `0` has one digit.
+#[inline]
+pub fn count_digits<T: Digits>(n: T) -> u32 {
+    n.count_digits()
+}
+
+/// The digits of a number, most significant first. See [`Digits::digits`].
+#[derive(Debug, Clone)]
+pub struct DigitsIter<T> {
+    n: T,
+    // The place value of the next digit, or 0 once done
+    divisor: T,
+}
+
+macro_rules! impl_digits {
+    ($($t:ty),*) => {$(
+        impl Digits for $t {
+            #[inline]
+            fn count_digits(self) -> u32 {
+                self.checked_ilog10().map_or(1, |log| log + 1)
+            }
+
+            #[inline]
+            fn checked_pow10(exp: u32) -> Option<Self> {
+                (10 as $t).checked_pow(exp)
+            }
+
+            #[inline]
+            fn split_digits_at(self, n: u32) -> (Self, Self) {
+                match Self::checked_pow10(n) {
+                    Some(pow) => (self / pow, self % pow),
+                    None => (0, self),
+                }
+            }
+
+            #[inline]
+            fn checked_concat(self, other: Self) -> Option<Self> {
+                match Self::checked_pow10(other.count_digits()) {
+                    Some(pow) => self.checked_mul(pow)?.checked_add(other),
+                    // Only a leading zero can be shifted past the top of the type
+                    None => (self == 0).then_some(other),
+                }
+            }
+
+            #[inline]
+            fn wrapping_concat(self, other: Self) -> Self {
+                // 10^n mod 2^bits, which is what the exact shift wraps to
+                let pow = (10 as $t).wrapping_pow(other.count_digits());
+                self.wrapping_mul(pow).wrapping_add(other)
+            }
+
+            #[inline]
+            fn digits(self) -> DigitsIter<Self> {
+                DigitsIter {
+                    n: self,
+                    // Always fits, since it's at most `self`
+                    divisor: (10 as $t).pow(self.count_digits() - 1),
+                }
+            }
+
+            fn checked_reverse_digits(self) -> Option<Self> {
+                let mut n = self;
+                let mut reversed: Self = 0;
+                loop {
+                    reversed = reversed.checked_mul(10)?.checked_add(n % 10)?;
+                    n /= 10;
+                    if n == 0 {
+                        return Some(reversed);
+                    }
+                }
+            }
+        }
+
+        impl Iterator for DigitsIter<$t> {
+            type Item = u8;
+
+            #[inline]
+            fn next(&mut self) -> Option<u8> {
+                if self.divisor == 0 {
+                    return None;
+                }
+                let digit = (self.n / self.divisor) % 10;
+                self.divisor /= 10;
+                Some(digit as u8)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = match self.divisor {
+                    0 => 0,
+                    divisor => divisor.count_digits() as usize,
+                };
+                (len, Some(len))
+            }
+        }
+
+        impl ExactSizeIterator for DigitsIter<$t> {}
+    )*};
+}
+
+impl_digits!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// Check every power of ten boundary for a type against its formatted length.
+    macro_rules! check_boundaries {
+        ($t:ty) => {{
+            let mut values: Vec<$t> = vec![0, 1, <$t>::MAX, <$t>::MAX - 1];
+            let mut pow: $t = 1;
+            while let Some(next) = pow.checked_mul(10) {
+                values.extend([next - 1, next, next + 1]);
+                pow = next;
+            }
+            for n in values {
+                let expected = n.to_string();
+                assert_eq!(n.count_digits() as usize, expected.len(), "{n}");
+                let digits: String = n.digits().map(|d| char::from(b'0' + d)).collect();
+                assert_eq!(digits, expected);
+                assert_eq!(n.digits().len(), expected.len());
+
+                let reversed = expected
+                    .chars()
+                    .rev()
+                    .collect::<String>()
+                    .parse::<$t>()
+                    .ok();
+                assert_eq!(n.checked_reverse_digits(), reversed, "{n}");
+
+                for at in 0..=expected.len() as u32 + 1 {
+                    let (high, low) = n.split_digits_at(at);
+                    let split = expected.len().saturating_sub(at as usize);
+                    assert_eq!(high, expected[..split].parse::<$t>().unwrap_or(0));
+                    assert_eq!(low, expected[split..].parse::<$t>().unwrap_or(0));
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_boundaries_all_widths() {
+        check_boundaries!(u8);
+        check_boundaries!(u16);
+        check_boundaries!(u32);
+        check_boundaries!(u64);
+        check_boundaries!(u128);
+        check_boundaries!(usize);
+    }
+
+    #[test]
+    fn test_exhaustive_u16() {
+        for n in 0..=u16::MAX {
+            assert_eq!(n.count_digits() as usize, n.to_string().len());
+        }
+    }
+
+    #[test]
+    fn test_exhaustive_concat_u8() {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                let exact: u128 = format!("{a}{b}").parse().unwrap();
+                assert_eq!(a.checked_concat(b), u8::try_from(exact).ok());
+                assert_eq!(a.wrapping_concat(b), exact as u8);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(9_999_999_999_999_999, 16)]
+    #[case(10_000_000_000_000_000, 17)]
+    #[case(9_007_199_254_740_993, 16)]
+    #[case(99_999_999_999_999_999, 17)]
+    #[case(u64::MAX, 20)]
+    fn test_count_digits_past_f64_precision(#[case] n: u64, #[case] expected: u32) {
+        assert_eq!(count_digits(n), expected);
+    }
+
+    #[rstest]
+    #[case(1234, 5768, Some(12345768))]
+    #[case(1, 0, Some(10))]
+    #[case(0, 7, Some(7))]
+    #[case(1_844_674_407_370_955_161, 5, Some(u64::MAX))]
+    #[case(1_844_674_407_370_955_161, 6, None)]
+    #[case(u64::MAX, 1, None)]
+    fn test_checked_concat(#[case] a: u64, #[case] b: u64, #[case] expected: Option<u64>) {
+        assert_eq!(a.checked_concat(b), expected);
+    }
+}
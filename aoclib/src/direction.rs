@@ -0,0 +1,192 @@
+use std::ops::{Add, AddAssign};
+
+use crate::grid::{Point, DOWN, LEFT, RIGHT, UP};
+
+/// One of the four orthogonal directions on a [`crate::grid::Grid`].
+///
+/// Directions are in clockwise order, so [`Direction::index`] can be used to index per
+/// direction arrays.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All four directions, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
+    /// Parse one of `^>v<`.
+    pub const fn from_arrow(byte: u8) -> Option<Self> {
+        match byte {
+            b'^' => Some(Direction::Up),
+            b'>' => Some(Direction::Right),
+            b'v' => Some(Direction::Down),
+            b'<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    /// Parse one of `NESW`, where north is up.
+    pub const fn from_compass(byte: u8) -> Option<Self> {
+        match byte {
+            b'N' => Some(Direction::Up),
+            b'E' => Some(Direction::Right),
+            b'S' => Some(Direction::Down),
+            b'W' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    /// Parse one of `URDL`.
+    pub const fn from_letter(byte: u8) -> Option<Self> {
+        match byte {
+            b'U' => Some(Direction::Up),
+            b'R' => Some(Direction::Right),
+            b'D' => Some(Direction::Down),
+            b'L' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    /// The unit step in this direction, or None if `point` isn't one.
+    pub const fn from_point(point: Point) -> Option<Self> {
+        match (point.x, point.y) {
+            (0, 1) => Some(Direction::Up),
+            (1, 0) => Some(Direction::Right),
+            (0, -1) => Some(Direction::Down),
+            (-1, 0) => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub const fn to_arrow(self) -> u8 {
+        b"^>v<"[self.index()]
+    }
+
+    pub const fn to_compass(self) -> u8 {
+        b"NESW"[self.index()]
+    }
+
+    pub const fn to_letter(self) -> u8 {
+        b"URDL"[self.index()]
+    }
+
+    /// The unit step in this direction. Grids count y up from the bottom, so up is `+y`.
+    pub const fn to_point(self) -> Point {
+        [UP, RIGHT, DOWN, LEFT][self.index()]
+    }
+
+    /// Position in [`Direction::ALL`].
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self.index() + 1) % 4]
+    }
+
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self.index() + 3) % 4]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 2) % 4]
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(direction: Direction) -> Self {
+        direction.to_point()
+    }
+}
+
+impl TryFrom<Point> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(point: Point) -> Result<Self, Self::Error> {
+        Direction::from_point(point)
+            .ok_or_else(|| anyhow::anyhow!("{:?} is not a unit step", point))
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point {
+        self + direction.to_point()
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, direction: Direction) {
+        *self += direction.to_point();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b'^', b'N', b'U', UP)]
+    #[case(b'>', b'E', b'R', RIGHT)]
+    #[case(b'v', b'S', b'D', DOWN)]
+    #[case(b'<', b'W', b'L', LEFT)]
+    fn test_conversions(
+        #[case] arrow: u8,
+        #[case] compass: u8,
+        #[case] letter: u8,
+        #[case] point: Point,
+    ) {
+        let direction = Direction::from_arrow(arrow).unwrap();
+        assert_eq!(Direction::from_compass(compass), Some(direction));
+        assert_eq!(Direction::from_letter(letter), Some(direction));
+        assert_eq!(Direction::try_from(point).unwrap(), direction);
+        assert_eq!(
+            (
+                direction.to_arrow(),
+                direction.to_compass(),
+                direction.to_letter(),
+                Point::from(direction)
+            ),
+            (arrow, compass, letter, point)
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(Direction::from_arrow(b'.'), None);
+        assert_eq!(Direction::from_compass(b'n'), None);
+        assert_eq!(Direction::from_letter(b'^'), None);
+        assert!(Direction::try_from(Point::new(1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_turns() {
+        for direction in Direction::iter() {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(
+                direction.opposite().to_point(),
+                Point::new(0, 0) - direction.to_point()
+            );
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Point::new(2, 2) + Direction::Left, Point::new(1, 2));
+    }
+}
@@ -37,11 +37,12 @@ impl Point {
     }
 }
 
+// The operator impls can't be `#[must_use]`, since rustc rejects it on trait impl methods, but
+// `std::ops` already warns when the result of `+`, `-` or `*` is unused.
 impl Add for Point {
     type Output = Self;
 
     #[inline]
-    #[must_use]
     fn add(self, rhs: Self) -> Self {
         Point::new(self.x + rhs.x, self.y + rhs.y)
     }
@@ -59,7 +60,6 @@ impl Mul<isize> for Point {
     type Output = Self;
 
     #[inline]
-    #[must_use]
     fn mul(self, rhs: isize) -> Self {
         Point::new(self.x * rhs, self.y * rhs)
     }
@@ -69,7 +69,6 @@ impl Sub for Point {
     type Output = Self;
 
     #[inline]
-    #[must_use]
     fn sub(self, rhs: Self) -> Self {
         Point::new(self.x - rhs.x, self.y - rhs.y)
     }
@@ -83,15 +82,6 @@ impl SubAssign for Point {
     }
 }
 
-pub const MOVE_UP: u8 = b'^';
-pub const MOVE_LEFT: u8 = b'<';
-pub const MOVE_RIGHT: u8 = b'>';
-pub const MOVE_DOWN: u8 = b'v';
-
-pub const fn is_move(c: u8) -> bool {
-    matches!(c, MOVE_UP | MOVE_DOWN | MOVE_LEFT | MOVE_RIGHT)
-}
-
 pub const ORIGIN: Point = Point::new(0, 0);
 pub const DOWN: Point = Point::new(0, -1);
 pub const UP: Point = Point::new(0, 1);
@@ -190,13 +180,13 @@ impl Grid {
         &mut self.data[index as usize]
     }
 
-    pub fn rows(&self) -> ViewIter {
+    pub fn rows(&self) -> ViewIter<'_> {
         Box::new((0..self.height as isize).map(move |y| {
             Box::new((0..self.width as isize).map(move |x| Point { x, y })) as PointIter
         }))
     }
 
-    pub fn columns(&self) -> ViewIter {
+    pub fn columns(&self) -> ViewIter<'_> {
         Box::new((0..self.width as isize).map(move |x| {
             Box::new((0..self.height as isize).map(move |y| Point { x, y })) as PointIter
         }))
@@ -0,0 +1,237 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::AddAssign;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use memmap2::Mmap;
+
+use crate::parsers::ParseError;
+
+/// Memory map an input file so it can be handed to any `process(&[u8])` without reading it all
+/// into memory first.
+pub fn map_file<P: AsRef<Path>>(path: P) -> Result<Mmap> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    // Safety: inputs aren't expected to be modified while a puzzle is running.
+    unsafe { Mmap::map(&file) }.with_context(|| format!("Failed to map {}", path.display()))
+}
+
+/// A chunk of whole records from [`Chunks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<'a> {
+    pub data: &'a [u8],
+    /// The number of newlines in all the chunks before this one.
+    ///
+    /// Add this to the line of a [`crate::parsers::ParseError`] from this chunk to locate it in
+    /// the whole input. It's always newlines, whatever [`Chunks::delimiter`] is, so the lines
+    /// still match the input's. With another delimiter a chunk can start partway through a line,
+    /// and then columns on its first line count from the start of the chunk.
+    pub lines_before: usize,
+}
+
+/// Read an input in chunks that always end on a record delimiter.
+///
+/// Each chunk holds only whole records (lines by default), so the `&[u8]` parsers in
+/// [`crate::parsers`] can run on every chunk as if it were the whole input, and never see a
+/// number split across a boundary. Records longer than the chunk size grow the buffer.
+pub struct Chunks<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    // Start of the bytes carried over from the last read
+    start: usize,
+    // End of the valid bytes in the buffer
+    end: usize,
+    chunk_size: usize,
+    delimiter: u8,
+    lines_before: usize,
+    eof: bool,
+}
+
+impl<R: Read> Chunks<R> {
+    const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+    /// Chunk `reader` on newlines, reading about 1MiB at a time.
+    pub fn new(reader: R) -> Self {
+        Self::with_chunk_size(reader, Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    pub fn with_chunk_size(reader: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be non-zero");
+        Self {
+            reader,
+            buffer: vec![],
+            start: 0,
+            end: 0,
+            chunk_size,
+            delimiter: b'\n',
+            lines_before: 0,
+            eof: false,
+        }
+    }
+
+    /// Split records on `delimiter` instead of newlines.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Read the next chunk, or None once the reader is exhausted.
+    pub fn next_chunk(&mut self) -> io::Result<Option<Chunk<'_>>> {
+        // Count the lines in the chunk we handed out last time
+        self.lines_before += memchr::memchr_iter(b'\n', &self.buffer[..self.start]).count();
+
+        // Move the partial record left over from the last read to the front
+        self.buffer.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        let mut searched = 0;
+
+        loop {
+            if let Some(i) = memchr::memrchr(self.delimiter, &self.buffer[searched..self.end]) {
+                self.start = searched + i + 1;
+                return Ok(Some(self.chunk()));
+            }
+            searched = self.end;
+
+            if self.eof {
+                if self.end == 0 {
+                    return Ok(None);
+                }
+                // Last record with no trailing delimiter
+                self.start = self.end;
+                return Ok(Some(self.chunk()));
+            }
+
+            if self.buffer.len() < self.end + self.chunk_size {
+                self.buffer.resize(self.end + self.chunk_size, 0);
+            }
+            let bytes_read = self.reader.read(&mut self.buffer[self.end..])?;
+            if bytes_read == 0 {
+                self.eof = true;
+            }
+            self.end += bytes_read;
+        }
+    }
+
+    /// Run `parse` on every chunk and add up what it returns.
+    ///
+    /// A [`ParseError`] is moved to its line in the whole input rather than the chunk.
+    pub fn sum_with<T: Default + AddAssign>(
+        mut self,
+        mut parse: impl FnMut(&[u8]) -> Result<T, ParseError>,
+    ) -> Result<T> {
+        let mut sum = T::default();
+        while let Some(chunk) = self.next_chunk()? {
+            sum += parse(chunk.data).map_err(|e| e.after_lines(chunk.lines_before))?;
+        }
+        Ok(sum)
+    }
+
+    fn chunk(&self) -> Chunk<'_> {
+        Chunk {
+            data: &self.buffer[..self.start],
+            lines_before: self.lines_before,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn collect_chunks(input: &[u8], chunk_size: usize) -> Vec<Vec<u8>> {
+        let mut chunks = Chunks::with_chunk_size(input, chunk_size);
+        let mut collected = vec![];
+        while let Some(chunk) = chunks.next_chunk().unwrap() {
+            collected.push(chunk.data.to_vec());
+        }
+        collected
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(3)]
+    #[case(7)]
+    #[case(1024)]
+    fn test_chunks_end_on_records(#[case] chunk_size: usize) {
+        let input = b"190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6";
+        let chunks = collect_chunks(input, chunk_size);
+        assert_eq!(chunks.concat(), input);
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert_eq!(chunk.last(), Some(&b'\n'));
+        }
+    }
+
+    #[test]
+    fn test_chunks_long_record() {
+        let input = b"1\n2345678901234567890\n2\n";
+        assert_eq!(
+            collect_chunks(input, 4),
+            vec![b"1\n".to_vec(), b"2345678901234567890\n2\n".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_chunks_empty() {
+        assert!(collect_chunks(b"", 4).is_empty());
+    }
+
+    #[test]
+    fn test_chunks_lines_before() {
+        let mut chunks = Chunks::with_chunk_size(&b"a\nb\nc\nd"[..], 2);
+        let mut lines_before = vec![];
+        while let Some(chunk) = chunks.next_chunk().unwrap() {
+            lines_before.push(chunk.lines_before);
+        }
+        assert_eq!(lines_before, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_chunks_lines_before_other_delimiter() {
+        let mut chunks = Chunks::with_chunk_size(&b"1,2\n3,\n4,5"[..], 2).delimiter(b',');
+        let mut chunks_and_lines = vec![];
+        while let Some(chunk) = chunks.next_chunk().unwrap() {
+            chunks_and_lines.push((chunk.data.to_vec(), chunk.lines_before));
+        }
+        assert_eq!(
+            chunks_and_lines,
+            vec![
+                (b"1,".to_vec(), 0),
+                (b"2\n3,".to_vec(), 0),
+                (b"\n4,".to_vec(), 1),
+                (b"5".to_vec(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sum_with() -> Result<()> {
+        let parse = |data: &[u8]| {
+            data.split(|b| *b == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| crate::parsers::expect_unsigned::<u64>(line, 0, "a number"))
+                .map(|parsed| parsed.map(|(n, _)| n))
+                .sum::<Result<u64, _>>()
+        };
+        let input = b"1\n20\n300\n4000";
+        for chunk_size in [1, 3, 1024] {
+            assert_eq!(
+                Chunks::with_chunk_size(&input[..], chunk_size).sum_with(parse)?,
+                4321
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunks_delimiter() {
+        let mut chunks = Chunks::with_chunk_size(&b"1,2,3"[..], 3).delimiter(b',');
+        let mut next = || chunks.next_chunk().unwrap().map(|c| c.data.to_vec());
+        assert_eq!(next(), Some(b"1,".to_vec()));
+        assert_eq!(next(), Some(b"2,".to_vec()));
+        assert_eq!(next(), Some(b"3".to_vec()));
+        assert_eq!(next(), None);
+    }
+}
@@ -0,0 +1,180 @@
+use std::ops::RangeInclusive;
+
+use crate::grid::{Grid, Point};
+use crate::math::gcd;
+
+/// The line through two distinct points, as the grid points on it.
+///
+/// Points on the line are `a + t * step` for integer `t`, where `step` is `b - a` divided by the
+/// gcd of its components, so no grid point in between is skipped. `b` is at `t == span`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line {
+    a: Point,
+    step: Point,
+    span: isize,
+}
+
+impl Line {
+    /// The line through `a` and `b`, or None if they're the same point.
+    pub fn through(a: Point, b: Point) -> Option<Self> {
+        let delta = b - a;
+        let span = gcd(delta.x.unsigned_abs() as u64, delta.y.unsigned_abs() as u64) as isize;
+        if span == 0 {
+            return None;
+        }
+        Some(Self {
+            a,
+            step: Point::new(delta.x / span, delta.y / span),
+            span,
+        })
+    }
+
+    /// The smallest step between grid points on the line, pointing from `a` towards `b`.
+    pub fn step(&self) -> Point {
+        self.step
+    }
+
+    /// The point `t` steps from `a`.
+    pub fn at(&self, t: isize) -> Point {
+        self.a + self.step * t
+    }
+
+    /// Every grid point on the line that's inside `grid`, in order from `a` towards `b`.
+    pub fn lattice_points<'a>(&self, grid: &'a Grid) -> impl Iterator<Item = Point> + 'a {
+        let line = *self;
+        self.steps_inside(grid).map(move |t| line.at(t))
+    }
+
+    /// The points from [`Line::lattice_points`] where one of `a` and `b` is `ratio` times as far
+    /// away as the other.
+    pub fn points_at_ratio<'a>(
+        &self,
+        grid: &'a Grid,
+        ratio: u64,
+    ) -> impl Iterator<Item = Point> + 'a {
+        let line = *self;
+        let ratio = ratio as isize;
+        self.steps_inside(grid)
+            .filter(move |t| {
+                // Distances along the line are proportional to the number of steps
+                let (to_a, to_b) = (t.abs(), (t - line.span).abs());
+                to_a == ratio * to_b || to_b == ratio * to_a
+            })
+            .map(move |t| line.at(t))
+    }
+
+    /// The values of `t` that are inside `grid`.
+    fn steps_inside(&self, grid: &Grid) -> RangeInclusive<isize> {
+        let (x_min, x_max) = t_range(self.a.x, self.step.x, grid.num_cols() as isize);
+        let (y_min, y_max) = t_range(self.a.y, self.step.y, grid.num_rows() as isize);
+        x_min.max(y_min)..=x_max.min(y_max)
+    }
+}
+
+/// The range of `t` that keeps `start + t * step` in `0..len`, or everything if `step` is 0 and
+/// `start` is in range.
+fn t_range(start: isize, step: isize, len: isize) -> (isize, isize) {
+    match step.signum() {
+        0 if (0..len).contains(&start) => (isize::MIN, isize::MAX),
+        0 => (1, 0),
+        1 => (ceil_div(-start, step), (len - 1 - start).div_euclid(step)),
+        _ => (ceil_div(start - (len - 1), -step), start.div_euclid(-step)),
+    }
+}
+
+/// `a / b` rounded up, for a positive `b`.
+fn ceil_div(a: isize, b: isize) -> isize {
+    -(-a).div_euclid(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn grid(width: usize, height: usize) -> Grid {
+        let row = ".".repeat(width) + "\n";
+        Grid::new(row.repeat(height).as_bytes()).unwrap()
+    }
+
+    #[rstest]
+    #[case(Point::new(1, 1), Point::new(5, 3), Point::new(2, 1))]
+    #[case(Point::new(5, 3), Point::new(1, 1), Point::new(-2, -1))]
+    #[case(Point::new(0, 0), Point::new(0, 6), Point::new(0, 1))]
+    #[case(Point::new(4, 0), Point::new(1, 3), Point::new(-1, 1))]
+    fn test_step(#[case] a: Point, #[case] b: Point, #[case] expected: Point) {
+        assert_eq!(Line::through(a, b).unwrap().step(), expected);
+    }
+
+    #[test]
+    fn test_same_point() {
+        assert_eq!(Line::through(Point::new(2, 2), Point::new(2, 2)), None);
+    }
+
+    #[test]
+    fn test_lattice_points() {
+        let grid = grid(10, 6);
+        let line = Line::through(Point::new(3, 2), Point::new(5, 3)).unwrap();
+        assert_eq!(
+            line.lattice_points(&grid).collect::<Vec<_>>(),
+            vec![
+                Point::new(1, 1),
+                Point::new(3, 2),
+                Point::new(5, 3),
+                Point::new(7, 4),
+                Point::new(9, 5),
+            ]
+        );
+
+        let line = Line::through(Point::new(2, 4), Point::new(2, 1)).unwrap();
+        assert_eq!(
+            line.lattice_points(&grid).collect::<Vec<_>>(),
+            (0..6).rev().map(|y| Point::new(2, y)).collect::<Vec<_>>()
+        );
+
+        // Points off the grid still describe a line that can cross it
+        let line = Line::through(Point::new(-2, -1), Point::new(0, 0)).unwrap();
+        assert_eq!(line.lattice_points(&grid).count(), 5);
+        let line = Line::through(Point::new(-1, 0), Point::new(-1, 1)).unwrap();
+        assert_eq!(line.lattice_points(&grid).count(), 0);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let grid = grid(7, 5);
+        for a in grid.points() {
+            for b in grid.points().filter(|b| *b != a) {
+                let line = Line::through(a, b).unwrap();
+                let expected: Vec<_> = grid
+                    .points()
+                    .filter(|p| {
+                        let (ap, ab) = (*p - a, b - a);
+                        ap.x * ab.y == ap.y * ab.x
+                    })
+                    .collect();
+                let mut points: Vec<_> = line.lattice_points(&grid).collect();
+                points.sort_by_key(|p| (p.y, p.x));
+                assert_eq!(points, expected, "{a:?} {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_points_at_ratio() {
+        let grid = grid(12, 3);
+        let line = Line::through(Point::new(3, 1), Point::new(6, 1)).unwrap();
+        assert_eq!(
+            line.points_at_ratio(&grid, 2).collect::<Vec<_>>(),
+            vec![
+                Point::new(0, 1),
+                Point::new(4, 1),
+                Point::new(5, 1),
+                Point::new(9, 1),
+            ]
+        );
+        assert_eq!(
+            line.points_at_ratio(&grid, 1).collect::<Vec<_>>(),
+            Vec::<Point>::new()
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Number theory helpers: gcd/lcm, modular arithmetic, and the Chinese Remainder Theorem.
+//!
+//! Modular multiplication goes through `u128`, so none of these overflow for any `u64` modulus.
+
+/// Greatest common divisor, where `gcd(0, 0) == 0`.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or None if it doesn't fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Extended Euclid, returning `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+///
+/// `g` is never negative, except that a gcd of `2^63` doesn't fit in an `i64` and wraps to
+/// `i64::MIN`. That only happens when `a` and `b` are each `0` or `i64::MIN`, and not both `0`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    // i128 so the coefficients can't overflow on the way, they always fit back into an i64
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_x, mut x) = (1_i128, 0_i128);
+    let (mut old_y, mut y) = (0_i128, 1_i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (old_r, old_x, old_y) = (-old_r, -old_x, -old_y);
+    }
+    (old_r as i64, old_x as i64, old_y as i64)
+}
+
+/// `a * b % modulus` without overflowing.
+///
+/// # Panics
+///
+/// If `modulus` is 0.
+#[inline]
+pub fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+/// `base ^ exp % modulus` by repeated squaring, without overflowing.
+///
+/// # Panics
+///
+/// If `modulus` is 0.
+pub fn pow_mod(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The `x` in `0..modulus` with `a * x % modulus == 1`, or None if `a` and `modulus` aren't
+/// coprime.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    // Same as `extended_gcd`, but in i128 so any u64 fits
+    let (mut old_r, mut r) = ((a % modulus) as i128, modulus as i128);
+    let (mut old_x, mut x) = (1_i128, 0_i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+    }
+    if old_r != 1 {
+        // `modulus == 1` is the one case where everything is the inverse, 0 included
+        return (modulus == 1).then_some(0);
+    }
+    Some(old_x.rem_euclid(modulus as i128) as u64)
+}
+
+/// Solve the system `x ≡ residue (mod modulus)` for every `(residue, modulus)` pair.
+///
+/// Returns the smallest non-negative `x` together with the lcm of the moduli, so every solution
+/// is `x + k * lcm`. The moduli don't need to be coprime. Returns None if the congruences
+/// contradict each other, a modulus is `0`, or the lcm doesn't fit in a `u64`. An empty system is
+/// solved by `(0, 1)`.
+pub fn crt(congruences: impl IntoIterator<Item = (u64, u64)>) -> Option<(u64, u64)> {
+    let mut solution = (0, 1);
+    for (residue, modulus) in congruences {
+        if modulus == 0 {
+            return None;
+        }
+        solution = merge_congruences(solution, (residue % modulus, modulus))?;
+    }
+    Some(solution)
+}
+
+/// Combine `x ≡ r1 (mod m1)` and `x ≡ r2 (mod m2)` into a single congruence.
+fn merge_congruences((r1, m1): (u64, u64), (r2, m2): (u64, u64)) -> Option<(u64, u64)> {
+    let g = gcd(m1, m2);
+    // x = r1 + m1 * k, so m1 * k ≡ r2 - r1 (mod m2), which needs g to divide r2 - r1
+    let diff = (r2 as i128 - r1 as i128).rem_euclid(m2 as i128) as u64;
+    if !diff.is_multiple_of(g) {
+        return None;
+    }
+    let m2_reduced = m2 / g;
+    let k = mul_mod(
+        diff / g,
+        mod_inverse((m1 / g) % m2_reduced, m2_reduced)?,
+        m2_reduced,
+    );
+    let lcm = (m1 / g).checked_mul(m2)?;
+    // r1 < m1 and m1 * k < lcm, so the sum fits in a u128
+    let x = (r1 as u128 + m1 as u128 * k as u128) % lcm as u128;
+    Some((x as u64, lcm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0, 0)]
+    #[case(0, 7, 7)]
+    #[case(12, 18, 6)]
+    #[case(17, 5, 1)]
+    #[case(u64::MAX, u64::MAX - 1, 1)]
+    fn test_gcd(#[case] a: u64, #[case] b: u64, #[case] expected: u64) {
+        assert_eq!(gcd(a, b), expected);
+        assert_eq!(gcd(b, a), expected);
+    }
+
+    #[rstest]
+    #[case(4, 6, Some(12))]
+    #[case(0, 6, Some(0))]
+    #[case(101, 103, Some(10403))]
+    #[case(u64::MAX, 2, None)]
+    fn test_lcm(#[case] a: u64, #[case] b: u64, #[case] expected: Option<u64>) {
+        assert_eq!(lcm(a, b), expected);
+    }
+
+    #[rstest]
+    #[case(240, 46)]
+    #[case(-240, 46)]
+    #[case(0, 5)]
+    #[case(i64::MAX, i64::MAX - 1)]
+    fn test_extended_gcd(#[case] a: i64, #[case] b: i64) {
+        let (g, x, y) = extended_gcd(a, b);
+        assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i64);
+        assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+    }
+
+    #[rstest]
+    #[case(i64::MIN, 0)]
+    #[case(0, i64::MIN)]
+    #[case(i64::MIN, i64::MIN)]
+    fn test_extended_gcd_too_big(#[case] a: i64, #[case] b: i64) {
+        let (g, x, y) = extended_gcd(a, b);
+        assert_eq!(g, i64::MIN);
+        // Still right once it wraps
+        assert_eq!(a.wrapping_mul(x).wrapping_add(b.wrapping_mul(y)), g);
+    }
+
+    #[rstest]
+    #[case(3, 11, Some(4))]
+    #[case(101, 103, Some(51))]
+    #[case(6, 9, None)]
+    #[case(5, 1, Some(0))]
+    #[case(2, u64::MAX, Some(u64::MAX / 2 + 1))]
+    fn test_mod_inverse(#[case] a: u64, #[case] modulus: u64, #[case] expected: Option<u64>) {
+        assert_eq!(mod_inverse(a, modulus), expected);
+        if let Some(inverse) = expected {
+            assert_eq!(mul_mod(a, inverse, modulus), 1 % modulus);
+        }
+    }
+
+    #[rstest]
+    #[case(2, 10, 1000, 24)]
+    #[case(3, 0, 7, 1)]
+    #[case(5, 3, 1, 0)]
+    #[case(u64::MAX - 1, u64::MAX, u64::MAX, u64::MAX - 1)]
+    #[case(2, u64::MAX - 59, u64::MAX - 58, 1)] // Fermat, 2^64 - 59 is prime
+    fn test_pow_mod(
+        #[case] base: u64,
+        #[case] exp: u64,
+        #[case] modulus: u64,
+        #[case] expected: u64,
+    ) {
+        assert_eq!(pow_mod(base, exp, modulus), expected);
+    }
+
+    #[rstest]
+    #[case(vec![(2, 3), (3, 5), (2, 7)], Some((23, 105)))]
+    #[case(vec![(2, 4), (4, 6)], Some((10, 12)))]
+    #[case(vec![(1, 4), (2, 6)], None)]
+    #[case(vec![(7, 101), (8, 103)], Some((5158, 10403)))]
+    #[case(vec![], Some((0, 1)))]
+    #[case(vec![(1, 0)], None)]
+    #[case(vec![(0, u64::MAX), (0, u64::MAX - 1)], None)]
+    fn test_crt(#[case] congruences: Vec<(u64, u64)>, #[case] expected: Option<(u64, u64)>) {
+        assert_eq!(crt(congruences.iter().copied()), expected);
+        if let Some((x, _)) = expected {
+            for (residue, modulus) in congruences {
+                assert_eq!(x % modulus, residue % modulus);
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
+
+/// What a memoised function calls to recurse, which answers from the cache when it can.
+pub type Recurse<'a, K, V> = dyn FnMut(K) -> V + 'a;
+
+/// A function that caches every value it computes, including the ones it computes for its own
+/// recursive calls.
+///
+/// The function is written as plain recursion, taking a `recurse` callback in place of calling
+/// itself:
+///
+/// ```
+/// use aoclib::memo::Memo;
+///
+/// let mut fib = Memo::new(|recurse, n: u64| -> u64 {
+///     if n < 2 {
+///         n
+///     } else {
+///         recurse(n - 1) + recurse(n - 2)
+///     }
+/// });
+/// assert_eq!(fib.get(90), 2880067194370816120);
+/// ```
+pub struct Memo<K, V, F> {
+    cache: FxHashMap<K, V>,
+    f: F,
+}
+
+impl<K, V, F> Memo<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut Recurse<'_, K, V>, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            cache: FxHashMap::default(),
+            f,
+        }
+    }
+
+    /// The function's value at `key`, computing and caching it if needed.
+    pub fn get(&mut self, key: K) -> V {
+        compute(&mut self.cache, &self.f, key)
+    }
+
+    /// The value at `key` if it's already been computed.
+    pub fn cached(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Number of cached values.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+// A free function so the cache can be borrowed mutably by the callback while `f` is running
+fn compute<K, V, F>(cache: &mut FxHashMap<K, V>, f: &F, key: K) -> V
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut Recurse<'_, K, V>, K) -> V,
+{
+    if let Some(value) = cache.get(&key) {
+        return value.clone();
+    }
+    let value = f(&mut |key| compute(cache, f, key), key.clone());
+    cache.insert(key, value.clone());
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_each_key_computed_once() {
+        let calls = Cell::new(0);
+        let mut fib = Memo::new(|recurse, n: u64| -> u64 {
+            calls.set(calls.get() + 1);
+            if n < 2 {
+                n
+            } else {
+                recurse(n - 1) + recurse(n - 2)
+            }
+        });
+        assert_eq!(fib.get(50), 12586269025);
+        assert_eq!(calls.get(), 51);
+        assert_eq!(fib.len(), 51);
+        assert_eq!(fib.cached(&10), Some(&55));
+
+        assert_eq!(fib.get(40), 102334155);
+        assert_eq!(calls.get(), 51);
+
+        fib.clear();
+        assert!(fib.is_empty());
+        assert_eq!(fib.cached(&10), None);
+    }
+
+    #[test]
+    fn test_tuple_keys() {
+        // Lattice paths through an n by m grid
+        let mut paths = Memo::new(|recurse, (n, m): (u32, u32)| -> u64 {
+            if n == 0 || m == 0 {
+                1
+            } else {
+                recurse((n - 1, m)) + recurse((n, m - 1))
+            }
+        });
+        assert_eq!(paths.get((2, 2)), 6);
+        assert_eq!(paths.get((16, 16)), 601080390);
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use num::{FromPrimitive, Num};
 
 #[inline]
@@ -26,3 +28,452 @@ where
     }
     Some((num, bytes_read))
 }
+
+const ASCII_ZEROS: u64 = u64::from_ne_bytes([b'0'; 8]);
+const HIGH_NIBBLES: u64 = u64::from_ne_bytes([0xF0; 8]);
+const ADD_SIX: u64 = u64::from_ne_bytes([0x06; 8]);
+const ASCII_THREES: u64 = u64::from_ne_bytes([0x33; 8]);
+
+/// Load up to 8 bytes into a little endian word, padding with zeros (which are not digits).
+#[inline]
+fn load_word(bytes: &[u8]) -> u64 {
+    if bytes.len() >= 8 {
+        u64::from_le_bytes(bytes[..8].try_into().unwrap())
+    } else {
+        let mut buffer = [0; 8];
+        buffer[..bytes.len()].copy_from_slice(bytes);
+        u64::from_le_bytes(buffer)
+    }
+}
+
+/// Count how many of the (up to) 8 leading bytes in `word` are ascii digits.
+///
+/// A byte is a digit if its high nibble is 3, and its high nibble is still 3 after adding 6
+/// (which rules out `:` through `?`). Both nibble checks are folded into one byte so that digits
+/// become `0x33`, then anything that isn't `0x33` is the first non-digit.
+///
+/// Adding 6 can carry into the next byte, but only from bytes >= 0xFA, which aren't digits, so
+/// the carry only ever lands after the first non-digit.
+#[inline]
+fn leading_digits_in_word(word: u64) -> usize {
+    let high = word & HIGH_NIBBLES;
+    let shifted_high = (word.wrapping_add(ADD_SIX) & HIGH_NIBBLES) >> 4;
+    let non_digits = (high | shifted_high) ^ ASCII_THREES;
+    (non_digits.trailing_zeros() / 8) as usize
+}
+
+/// Convert the first `len` (1..=8) ascii digits of `word` into their numeric value.
+///
+/// The digits are shifted to the top of the word so shorter runs get leading zeros, then pairs,
+/// quads, and octets of digits are combined with three multiplies. Subtracting the ascii zeros
+/// can only borrow out of non-digit bytes, which are shifted out.
+#[inline]
+fn digits_in_word_to_num(word: u64, len: usize) -> u64 {
+    debug_assert!((1..=8).contains(&len));
+    let mut value = word.wrapping_sub(ASCII_ZEROS) << ((8 - len) * 8);
+    value = (value.wrapping_mul(10) + (value >> 8)) & 0x00FF_00FF_00FF_00FF;
+    value = (value.wrapping_mul(100) + (value >> 16)) & 0x0000_FFFF_0000_FFFF;
+    value = (value.wrapping_mul(10_000) + (value >> 32)) & 0x0000_0000_FFFF_FFFF;
+    value
+}
+
+const POW10: [u64; 9] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+];
+
+/// Count the number of leading ascii digits in a byte slice, 8 bytes at a time.
+#[inline]
+pub fn count_leading_digits(bytes: &[u8]) -> usize {
+    let mut offset = 0;
+    loop {
+        let digits = leading_digits_in_word(load_word(&bytes[offset..]));
+        offset += digits;
+        if digits < 8 {
+            return offset;
+        }
+    }
+}
+
+/// Parse the leading ascii digits of a byte slice as a u64, 8 digits at a time.
+///
+/// Returns None if there are no leading digits or if the number overflows a u64.
+/// The second element of the returned tuple is the number of bytes read.
+#[inline]
+pub fn parse_u64(bytes: &[u8]) -> Option<(u64, usize)> {
+    let word = load_word(bytes);
+    let digits = leading_digits_in_word(word);
+    if digits == 0 {
+        return None;
+    }
+    let mut num = digits_in_word_to_num(word, digits);
+    if digits < 8 {
+        return Some((num, digits));
+    }
+
+    // Numbers longer than 8 digits
+    let mut offset = digits;
+    loop {
+        let word = load_word(&bytes[offset..]);
+        let digits = leading_digits_in_word(word);
+        if digits == 0 {
+            break;
+        }
+        num = num
+            .checked_mul(POW10[digits])?
+            .checked_add(digits_in_word_to_num(word, digits))?;
+        offset += digits;
+        if digits < 8 {
+            break;
+        }
+    }
+    Some((num, offset))
+}
+
+/// Fast path for parsing an unsigned number from a byte slice.
+///
+/// Same contract as [`try_parse_num`], but returns None if the number does not fit in `T`
+/// instead of overflowing.
+#[inline]
+pub fn parse_unsigned<T>(bytes: &[u8]) -> Option<(T, usize)>
+where
+    T: FromPrimitive,
+{
+    let (num, bytes_read) = parse_u64(bytes)?;
+    Some((T::from_u64(num)?, bytes_read))
+}
+
+/// Fast path for parsing a signed number from a byte slice.
+///
+/// An optional leading `-` or `+` is accepted and counted in the number of bytes read.
+/// Returns None if no digits follow the sign or if the number does not fit in `T`.
+#[inline]
+pub fn parse_signed<T>(bytes: &[u8]) -> Option<(T, usize)>
+where
+    T: FromPrimitive,
+{
+    let (is_neg, sign_len) = match bytes.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ => (false, 0),
+    };
+    let (magnitude, bytes_read) = parse_u64(&bytes[sign_len..])?;
+    let num = if is_neg {
+        0_i64.checked_sub_unsigned(magnitude)?
+    } else {
+        i64::try_from(magnitude).ok()?
+    };
+    Some((T::from_i64(num)?, bytes_read + sign_len))
+}
+
+/// Find the first occurrence of a delimiter in a byte slice.
+#[inline]
+pub fn find_byte(delimiter: u8, bytes: &[u8]) -> Option<usize> {
+    memchr::memchr(delimiter, bytes)
+}
+
+/// Iterate over the newline delimited lines of a byte slice.
+///
+/// The newlines are not included and a trailing newline does not create an empty last line.
+pub fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> + '_ {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        if offset >= bytes.len() {
+            return None;
+        }
+        let rest = &bytes[offset..];
+        let end = find_byte(b'\n', rest).unwrap_or(rest.len());
+        offset += end + 1;
+        Some(&rest[..end])
+    })
+}
+
+/// A parse failure located by line and column in the original input.
+///
+/// Displays as the message and location followed by the offending line with a caret under the
+/// column, so it reads well when it bubbles up through `anyhow`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based byte column within the line
+    pub column: usize,
+    /// The offending line, windowed around the column if it is very long
+    pub snippet: String,
+    pub message: String,
+    // Position of the caret within the snippet
+    caret: usize,
+}
+
+impl ParseError {
+    // Lines longer than this are windowed around the column (day-09 is one 20k byte line)
+    const MAX_SNIPPET: usize = 60;
+
+    /// Create an error for the byte at `offset` in `input`.
+    ///
+    /// `input` must be the whole input, not a subslice, for the line and column to be right.
+    pub fn new(input: &[u8], offset: usize, message: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let line_start = memchr::memrchr(b'\n', &input[..offset]).map_or(0, |i| i + 1);
+        let line_end = find_byte(b'\n', &input[offset..]).map_or(input.len(), |i| offset + i);
+        let line = memchr::memchr_iter(b'\n', &input[..line_start]).count() + 1;
+        let column = offset - line_start + 1;
+
+        let window_start = line_start.max(offset.saturating_sub(Self::MAX_SNIPPET / 2));
+        let window_end = line_end.min(window_start + Self::MAX_SNIPPET);
+        Self {
+            line,
+            column,
+            snippet: String::from_utf8_lossy(&input[window_start..window_end]).into_owned(),
+            message: message.into(),
+            caret: offset - window_start,
+        }
+    }
+
+    /// Shift the error down by `lines`, for errors from a chunk of a larger input.
+    pub fn after_lines(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )?;
+        writeln!(f, "    {}", self.snippet)?;
+        write!(f, "    {:>width$}", "^", width = self.caret + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Describe the byte at `offset` for error messages.
+fn found(input: &[u8], offset: usize) -> String {
+    match input.get(offset) {
+        Some(b'\n') => "found a newline".to_string(),
+        Some(byte) => format!("found `{}`", byte.escape_ascii()),
+        None => "found end of input".to_string(),
+    }
+}
+
+/// Expect `expected` at `offset` in `input`, returning the offset just past it.
+pub fn expect_bytes(input: &[u8], offset: usize, expected: &[u8]) -> Result<usize, ParseError> {
+    for (i, byte) in expected.iter().enumerate() {
+        if input.get(offset + i) != Some(byte) {
+            return Err(ParseError::new(
+                input,
+                offset + i,
+                format!(
+                    "expected `{}`, {}",
+                    expected.escape_ascii(),
+                    found(input, offset + i)
+                ),
+            ));
+        }
+    }
+    Ok(offset + expected.len())
+}
+
+/// Parse an unsigned number at `offset` in `input`, returning it and the offset just past it.
+///
+/// `what` names the number in the error, e.g. "a page number".
+pub fn expect_unsigned<T>(input: &[u8], offset: usize, what: &str) -> Result<(T, usize), ParseError>
+where
+    T: FromPrimitive,
+{
+    let rest = input.get(offset..).unwrap_or_default();
+    match parse_unsigned::<T>(rest) {
+        Some((num, bytes_read)) => Ok((num, offset + bytes_read)),
+        None => Err(number_error::<T>(input, offset, what)),
+    }
+}
+
+/// Parse a signed number at `offset` in `input`, returning it and the offset just past it.
+///
+/// `what` names the number in the error, e.g. "a velocity".
+pub fn expect_signed<T>(input: &[u8], offset: usize, what: &str) -> Result<(T, usize), ParseError>
+where
+    T: FromPrimitive,
+{
+    let rest = input.get(offset..).unwrap_or_default();
+    match parse_signed::<T>(rest) {
+        Some((num, bytes_read)) => Ok((num, offset + bytes_read)),
+        None => Err(number_error::<T>(input, offset, what)),
+    }
+}
+
+fn number_error<T>(input: &[u8], offset: usize, what: &str) -> ParseError {
+    let rest = input.get(offset..).unwrap_or_default();
+    let sign_len = usize::from(matches!(rest.first(), Some(b'-' | b'+')));
+    if count_leading_digits(&rest[sign_len..]) > 0 {
+        ParseError::new(
+            input,
+            offset,
+            format!(
+                "expected {what}, but it does not fit in {}",
+                std::any::type_name::<T>()
+            ),
+        )
+    } else {
+        ParseError::new(
+            input,
+            offset + sign_len,
+            format!("expected {what}, {}", found(input, offset + sign_len)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"", 0)]
+    #[case(b"a", 0)]
+    #[case(b"7", 1)]
+    #[case(b"12:", 2)]
+    #[case(b"0123456789", 10)]
+    #[case(b"01234567/", 8)]
+    #[case(b"1234567890123456789 42", 19)]
+    #[case(b"9\xfa9", 1)]
+    fn test_count_leading_digits(#[case] input: &[u8], #[case] expected: usize) {
+        assert_eq!(count_leading_digits(input), expected);
+    }
+
+    #[rstest]
+    #[case(b"0", Some((0, 1)))]
+    #[case(b"42,13", Some((42, 2)))]
+    #[case(b"12345678", Some((12345678, 8)))]
+    #[case(b"123456789\n", Some((123456789, 9)))]
+    #[case(b"0000000000000001", Some((1, 16)))]
+    #[case(b"18446744073709551615", Some((u64::MAX, 20)))]
+    #[case(b"18446744073709551616", None)]
+    #[case(b"-1", None)]
+    #[case(b"", None)]
+    fn test_parse_u64(#[case] input: &[u8], #[case] expected: Option<(u64, usize)>) {
+        assert_eq!(parse_u64(input), expected);
+    }
+
+    #[test]
+    fn test_parse_u64_matches_try_parse_num() {
+        let mut buffer = Vec::new();
+        for n in (0..20)
+            .map(|exp| 10_u64.pow(exp))
+            .flat_map(|p| [p - 1, p, p + 1])
+        {
+            buffer.clear();
+            buffer.extend(n.to_string().bytes());
+            buffer.push(b' ');
+            assert_eq!(parse_u64(&buffer), try_parse_num::<u64>(&buffer));
+        }
+    }
+
+    #[rstest]
+    #[case(b"255", Some((255, 3)))]
+    #[case(b"256", None)]
+    #[case(b"7|", Some((7, 1)))]
+    fn test_parse_unsigned_u8(#[case] input: &[u8], #[case] expected: Option<(u8, usize)>) {
+        assert_eq!(parse_unsigned::<u8>(input), expected);
+    }
+
+    #[rstest]
+    #[case(b"-3,", Some((-3, 2)))]
+    #[case(b"+3", Some((3, 2)))]
+    #[case(b"17 ", Some((17, 2)))]
+    #[case(b"-9223372036854775808", Some((i64::MIN, 20)))]
+    #[case(b"9223372036854775808", None)]
+    #[case(b"-", None)]
+    #[case(b"-a", None)]
+    fn test_parse_signed(#[case] input: &[u8], #[case] expected: Option<(i64, usize)>) {
+        assert_eq!(parse_signed::<i64>(input), expected);
+    }
+
+    #[test]
+    fn test_lines() {
+        let input = b"ab\n\ncd\nef";
+        assert_eq!(
+            lines(input).collect::<Vec<_>>(),
+            vec![&b"ab"[..], b"", b"cd", b"ef"]
+        );
+        assert_eq!(lines(b"ab\n").collect::<Vec<_>>(), vec![&b"ab"[..]]);
+        assert_eq!(lines(b"").count(), 0);
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let input = b"47|53\n97|1x\n";
+        let err = ParseError::new(input, 10, "expected a page number");
+        assert_eq!((err.line, err.column), (2, 5));
+        assert_eq!(err.snippet, "97|1x");
+        assert_eq!(
+            err.to_string(),
+            "expected a page number at line 2, column 5\n    97|1x\n        ^"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_long_line_is_windowed() {
+        let input = [b'1'; 1000];
+        let err = ParseError::new(&input, 500, "nope");
+        assert_eq!((err.line, err.column), (1, 501));
+        assert_eq!(err.snippet.len(), ParseError::MAX_SNIPPET);
+        assert!(err.to_string().ends_with(&format!("{:>31}", "^")));
+    }
+
+    #[test]
+    fn test_parse_error_into_anyhow() {
+        fn parse(input: &[u8]) -> anyhow::Result<u8> {
+            let (num, _) = expect_unsigned::<u8>(input, 2, "a number")?;
+            Ok(num)
+        }
+        let err = parse(b"p=x").unwrap_err();
+        let parse_err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(parse_err.message, "expected a number, found `x`");
+        assert_eq!(parse(b"p=42").unwrap(), 42);
+    }
+
+    #[rstest]
+    #[case(b"p=3", 0, b"p=", Ok(2))]
+    #[case(b"p=3", 1, b"p=", Err("expected `p=`, found `=`"))]
+    #[case(b"p", 0, b"p=", Err("expected `p=`, found end of input"))]
+    fn test_expect_bytes(
+        #[case] input: &[u8],
+        #[case] offset: usize,
+        #[case] expected: &[u8],
+        #[case] result: Result<usize, &str>,
+    ) {
+        assert_eq!(
+            expect_bytes(input, offset, expected).map_err(|e| e.message),
+            result.map_err(|e| e.to_string())
+        );
+    }
+
+    #[rstest]
+    #[case(b"v=-3,2", 2, Ok((-3, 4)))]
+    #[case(b"v=-x", 2, Err("expected a velocity, found `x`"))]
+    #[case(b"v=\n", 2, Err("expected a velocity, found a newline"))]
+    #[case(b"v=99999", 2, Err("expected a velocity, but it does not fit in i16"))]
+    #[case(b"v=", 9, Err("expected a velocity, found end of input"))]
+    fn test_expect_signed(
+        #[case] input: &[u8],
+        #[case] offset: usize,
+        #[case] result: Result<(i16, usize), &str>,
+    ) {
+        assert_eq!(
+            expect_signed::<i16>(input, offset, "a velocity").map_err(|e| e.message),
+            result.map_err(|e| e.to_string())
+        );
+    }
+}
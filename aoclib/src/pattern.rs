@@ -0,0 +1,212 @@
+use anyhow::{bail, Result};
+
+use crate::grid::{Grid, Point};
+
+/// A small 2D template to search a [`Grid`] for, where some cells can be anything.
+///
+/// Cells are stored the way the pattern is written, top row first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    // None matches any byte
+    cells: Vec<Option<u8>>,
+}
+
+impl Pattern {
+    /// Parse a pattern written as newline separated rows, where `wildcard` matches anything.
+    ///
+    /// One trailing newline is allowed, but any other empty row is an error rather than being
+    /// skipped, since it would shift every row below it. A row of all wildcards can stand in for
+    /// a row that should match anything.
+    pub fn new(text: &[u8], wildcard: u8) -> Result<Self> {
+        let text = text.strip_suffix(b"\n").unwrap_or(text);
+        let rows: Vec<&[u8]> = text.split(|b| *b == b'\n').collect();
+        if let Some(row) = rows.iter().position(|row| row.is_empty()) {
+            bail!("Row {row} of the pattern is empty");
+        }
+        let width = rows[0].len();
+        if rows.iter().any(|row| row.len() != width) {
+            bail!("Every row of a pattern needs to be the same width");
+        }
+        Ok(Self {
+            width,
+            height: rows.len(),
+            cells: rows
+                .concat()
+                .into_iter()
+                .map(|b| (b != wildcard).then_some(b))
+                .collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `row` from the top and `col` from the left, or None for a wildcard.
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        self.cells[row * self.width + col]
+    }
+
+    /// Rotate 90 degrees clockwise.
+    pub fn rotate(&self) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for row in 0..self.width {
+            for col in 0..self.height {
+                // The new top row is the old left column, read bottom up
+                cells.push(self.get(self.height - 1 - col, row));
+            }
+        }
+        Self {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Mirror left to right.
+    pub fn reflect(&self) -> Self {
+        let mut cells = self.cells.clone();
+        for row in cells.chunks_exact_mut(self.width) {
+            row.reverse();
+        }
+        Self { cells, ..*self }
+    }
+
+    /// Every distinct rotation and reflection, starting with `self`.
+    ///
+    /// Symmetric patterns have fewer than eight, since orientations that come out the same are
+    /// dropped. Orientations that differ can still match the same cells when the pattern has
+    /// wildcards.
+    pub fn orientations(&self) -> Vec<Pattern> {
+        let mut orientations: Vec<Pattern> = vec![];
+        for mut pattern in [self.clone(), self.reflect()] {
+            for _ in 0..4 {
+                let next = pattern.rotate();
+                if !orientations.contains(&pattern) {
+                    orientations.push(pattern);
+                }
+                pattern = next;
+            }
+        }
+        orientations
+    }
+
+    /// Whether the pattern matches with its top left cell on `top_left`.
+    pub fn matches_at(&self, grid: &Grid, top_left: Point) -> bool {
+        let bottom_right = Point::new(
+            top_left.x + self.width as isize - 1,
+            top_left.y - (self.height as isize - 1),
+        );
+        if !grid.contains(top_left) || !grid.contains(bottom_right) {
+            return false;
+        }
+        (0..self.height).all(|row| {
+            (0..self.width).all(|col| {
+                // Grids count y up from the bottom, patterns count rows down from the top
+                let point = Point::new(top_left.x + col as isize, top_left.y - row as isize);
+                self.get(row, col)
+                    .is_none_or(|expected| grid.get_point(point) == expected)
+            })
+        })
+    }
+
+    /// The top left corner of every match in `grid`.
+    pub fn find_all<'a>(&'a self, grid: &'a Grid) -> impl Iterator<Item = Point> + 'a {
+        grid.points().filter(|point| self.matches_at(grid, *point))
+    }
+
+    /// Every match of any orientation, with the orientation that matched.
+    ///
+    /// A spot that more than one orientation matches is reported once for each of them.
+    pub fn find_all_orientations(&self, grid: &Grid) -> Vec<(Point, Pattern)> {
+        self.orientations()
+            .into_iter()
+            .flat_map(|pattern| {
+                pattern
+                    .find_all(grid)
+                    .map(|point| (point, pattern.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_and_reflect() -> Result<()> {
+        let pattern = Pattern::new(b"AB.\nCDE", b'.')?;
+        assert_eq!(pattern.rotate(), Pattern::new(b"CA\nDB\nE.", b'.')?);
+        assert_eq!(pattern.reflect(), Pattern::new(b".BA\nEDC", b'.')?);
+        assert_eq!(pattern.rotate().rotate().rotate().rotate(), pattern);
+        Ok(())
+    }
+
+    #[test]
+    fn test_orientations_are_distinct() -> Result<()> {
+        assert_eq!(Pattern::new(b"AB.\nCDE", b'.')?.orientations().len(), 8);
+        assert_eq!(
+            Pattern::new(b"M.S\n.A.\nM.S", b'.')?.orientations().len(),
+            4
+        );
+        assert_eq!(Pattern::new(b"X", b'.')?.orientations().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_all() -> Result<()> {
+        let grid = Grid::new(b"AB..\n.CAB\n...C\n")?;
+        let pattern = Pattern::new(b"AB\n.C", b'.')?;
+        assert_eq!(
+            pattern.find_all(&grid).collect::<Vec<_>>(),
+            vec![Point::new(2, 1), Point::new(0, 2)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_at_edges() -> Result<()> {
+        let grid = Grid::new(b"AB\nCD\n")?;
+        let pattern = Pattern::new(b"..\n..", b'.')?;
+        assert!(pattern.matches_at(&grid, Point::new(0, 1)));
+        assert!(!pattern.matches_at(&grid, Point::new(1, 1)));
+        assert!(!pattern.matches_at(&grid, Point::new(0, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_orientations_matching_the_same_cells() -> Result<()> {
+        let grid = Grid::new(b"AA\nAA")?;
+        let hits = Pattern::new(b"A.\n.A", b'.')?.find_all_orientations(&grid);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|(point, _)| *point == Point::new(0, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Pattern::new(b"", b'.').is_err());
+        assert!(Pattern::new(b"AB\nC", b'.').is_err());
+        assert!(Pattern::new(b"\n", b'.').is_err());
+        assert!(Pattern::new(b"AB\n\nCD", b'.').is_err());
+        assert!(Pattern::new(b"\nAB", b'.').is_err());
+        assert!(Pattern::new(b"AB\n\n", b'.').is_err());
+    }
+
+    #[test]
+    fn test_trailing_newline() -> Result<()> {
+        assert_eq!(
+            Pattern::new(b"AB\n.C\n", b'.')?,
+            Pattern::new(b"AB\n.C", b'.')?
+        );
+        Ok(())
+    }
+}
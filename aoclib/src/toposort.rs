@@ -0,0 +1,169 @@
+use std::{cmp::Reverse, collections::BinaryHeap, fmt, hash::Hash};
+
+use rustc_hash::FxHashMap;
+
+/// Nodes that depend on each other in a loop, each one before the next and the last before the
+/// first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N> {
+    pub nodes: Vec<N>,
+}
+
+impl<N: fmt::Debug> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle in ordering: ")?;
+        for node in &self.nodes {
+            write!(f, "{node:?} -> ")?;
+        }
+        write!(f, "{:?}", self.nodes[0])
+    }
+}
+
+impl<N: fmt::Debug> std::error::Error for Cycle<N> {}
+
+/// Order `nodes` so that for every `(before, after)` edge, `before` comes first.
+///
+/// Whenever more than one node could come next, the one given first is taken, so nodes with no
+/// order between them keep the order they were given in. Duplicate nodes are only kept once.
+/// Edges touching a node that isn't in `nodes` are ignored, so a big set of rules can be
+/// restricted to just the nodes at hand.
+pub fn toposort<N: Hash + Eq + Clone>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, Cycle<N>> {
+    let mut index = FxHashMap::default();
+    let mut unique = vec![];
+    for node in nodes {
+        index.entry(node.clone()).or_insert_with(|| {
+            unique.push(node);
+            unique.len() - 1
+        });
+    }
+
+    let mut successors = vec![vec![]; unique.len()];
+    let mut predecessors = vec![vec![]; unique.len()];
+    let mut in_degree = vec![0; unique.len()];
+    for (before, after) in edges {
+        if let (Some(&before), Some(&after)) = (index.get(&before), index.get(&after)) {
+            successors[before].push(after);
+            predecessors[after].push(before);
+            in_degree[after] += 1;
+        }
+    }
+
+    // Kahn's algorithm, with a min-heap of indices so the earliest ready node always goes next
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..unique.len())
+        .filter(|i| in_degree[*i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut sorted = Vec::with_capacity(unique.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        sorted.push(node);
+        for &next in &successors[node] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+
+    if sorted.len() < unique.len() {
+        return Err(Cycle {
+            nodes: find_cycle(&predecessors, &in_degree)
+                .into_iter()
+                .map(|i| unique[i].clone())
+                .collect(),
+        });
+    }
+    Ok(sorted.into_iter().map(|i| unique[i].clone()).collect())
+}
+
+/// Every node Kahn's algorithm couldn't place still has a predecessor it couldn't place, so
+/// walking backwards through those has to come back around.
+fn find_cycle(predecessors: &[Vec<usize>], in_degree: &[usize]) -> Vec<usize> {
+    let stuck = |node: &usize| in_degree[*node] > 0;
+    let mut node = (0..in_degree.len()).find(stuck).expect("a node is stuck");
+    let mut seen_at = vec![None; in_degree.len()];
+    let mut path = vec![];
+    while seen_at[node].is_none() {
+        seen_at[node] = Some(path.len());
+        path.push(node);
+        node = *predecessors[node]
+            .iter()
+            .find(|pred| stuck(pred))
+            .expect("a stuck node has a stuck predecessor");
+    }
+    let mut cycle = path.split_off(seen_at[node].unwrap());
+    // The walk went backwards
+    cycle.reverse();
+    cycle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_respects(sorted: &[u32], edges: &[(u32, u32)]) {
+        for (before, after) in edges {
+            let before = sorted.iter().position(|n| n == before);
+            let after = sorted.iter().position(|n| n == after);
+            if let (Some(before), Some(after)) = (before, after) {
+                assert!(before < after, "{sorted:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        let edges = [
+            (5, 11),
+            (7, 11),
+            (7, 8),
+            (3, 8),
+            (3, 10),
+            (11, 2),
+            (11, 9),
+            (8, 9),
+        ];
+        let sorted = toposort([2, 3, 5, 7, 8, 9, 10, 11], edges).unwrap();
+        assert_eq!(sorted.len(), 8);
+        assert_respects(&sorted, &edges);
+    }
+
+    #[test]
+    fn test_unordered_nodes_keep_their_order() {
+        assert_eq!(toposort([3, 1, 2, 1], []), Ok(vec![3, 1, 2]));
+        assert_eq!(toposort([3, 1, 2], [(2, 3)]), Ok(vec![1, 2, 3]));
+        // D is ready before C, but C was given first so still comes first
+        assert_eq!(
+            toposort(["A", "B", "C", "D"], [("A", "D"), ("B", "C")]),
+            Ok(vec!["A", "B", "C", "D"])
+        );
+    }
+
+    #[test]
+    fn test_edges_outside_nodes_are_ignored() {
+        let edges = [(1, 2), (2, 3), (3, 1), (4, 2)];
+        assert_eq!(toposort([2, 4, 1], edges), Ok(vec![4, 1, 2]));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let err = toposort([1, 2, 3, 4], [(4, 1), (1, 2), (2, 3), (3, 1)]).unwrap_err();
+        // Any rotation of the cycle would do
+        let mut nodes = err.nodes;
+        let start = nodes.iter().position(|n| *n == 1).unwrap();
+        nodes.rotate_left(start);
+        assert_eq!(nodes, vec![1, 2, 3]);
+        assert_eq!(
+            Cycle {
+                nodes: vec![1, 2, 3]
+            }
+            .to_string(),
+            "cycle in ordering: 1 -> 2 -> 3 -> 1"
+        );
+
+        let err = toposort(["a"], [("a", "a")]).unwrap_err();
+        assert_eq!(err.nodes, vec!["a"]);
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::VecDeque;
+
+use anyhow::{bail, Result};
+
+use crate::grid::{Grid, Point, DOWN_RIGHT, RIGHT, UP, UP_RIGHT};
+
+/// The headings each line of the grid is read in. Reading the reversed words along them finds
+/// the other four.
+const LINE_HEADINGS: [Point; 4] = [RIGHT, UP, UP_RIGHT, DOWN_RIGHT];
+
+/// A word found in the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hit {
+    /// Index of the word in the list the search was built from.
+    pub word: usize,
+    /// Where the first letter is.
+    pub start: Point,
+    /// The unit step from each letter to the next, one of [`crate::grid::ALL_DIRECTIONS`].
+    pub step: Point,
+}
+
+/// A word a node of the automaton completes, as it reads in the line.
+#[derive(Debug, Clone, Copy)]
+struct Output {
+    word: usize,
+    len: usize,
+    reversed: bool,
+}
+
+/// Finds any number of words in every direction of a grid.
+///
+/// The words and their reverses go in one Aho-Corasick automaton, which then makes a single
+/// pass over every row, column and diagonal.
+#[derive(Debug, Clone)]
+pub struct WordSearch {
+    // A full transition table per node, so reading a byte is one lookup
+    transitions: Vec<[u32; 256]>,
+    outputs: Vec<Vec<Output>>,
+}
+
+impl WordSearch {
+    /// Build a search for `words`. Palindromes are only searched for once, so each is found once
+    /// per line it's in rather than once in each direction.
+    pub fn new<W: AsRef<[u8]>>(words: impl IntoIterator<Item = W>) -> Result<Self> {
+        let mut search = Self {
+            transitions: vec![[0; 256]],
+            outputs: vec![vec![]],
+        };
+        let mut num_words = 0;
+        for (word, text) in words.into_iter().enumerate() {
+            let text = text.as_ref();
+            if text.is_empty() {
+                bail!("Word {word} is empty");
+            }
+            let reversed: Vec<u8> = text.iter().rev().copied().collect();
+            search.insert(text, word, false);
+            if reversed != text {
+                search.insert(&reversed, word, true);
+            }
+            num_words += 1;
+        }
+        if num_words == 0 {
+            bail!("A word search needs at least one word");
+        }
+        search.link();
+        Ok(search)
+    }
+
+    /// Add a word to the trie, where 0 in `transitions` still means no edge.
+    fn insert(&mut self, text: &[u8], word: usize, reversed: bool) {
+        let mut node = 0;
+        for &byte in text {
+            let next = self.transitions[node][byte as usize] as usize;
+            node = if next == 0 {
+                self.transitions.push([0; 256]);
+                self.outputs.push(vec![]);
+                let new = self.transitions.len() - 1;
+                self.transitions[node][byte as usize] = new as u32;
+                new
+            } else {
+                next
+            };
+        }
+        self.outputs[node].push(Output {
+            word,
+            len: text.len(),
+            reversed,
+        });
+    }
+
+    /// Turn the trie into a DFA, breadth first so each node's failure link is done before its
+    /// children need it.
+    fn link(&mut self) {
+        let mut fail = vec![0; self.transitions.len()];
+        let mut queue: VecDeque<usize> = self.transitions[0]
+            .iter()
+            .filter(|next| **next != 0)
+            .map(|next| *next as usize)
+            .collect();
+        while let Some(node) = queue.pop_front() {
+            let suffix_outputs = self.outputs[fail[node]].clone();
+            self.outputs[node].extend(suffix_outputs);
+            for byte in 0..256 {
+                let next = self.transitions[node][byte] as usize;
+                let fallback = self.transitions[fail[node]][byte];
+                if next == 0 {
+                    self.transitions[node][byte] = fallback;
+                } else {
+                    fail[next] = fallback as usize;
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    /// Every hit in `grid`, in no particular order.
+    pub fn find_all(&self, grid: &Grid) -> Vec<Hit> {
+        let mut hits = vec![];
+        self.walk(grid, |point, heading, output| {
+            hits.push(if output.reversed {
+                Hit {
+                    word: output.word,
+                    start: point,
+                    step: Point::new(0, 0) - heading,
+                }
+            } else {
+                Hit {
+                    word: output.word,
+                    start: point - heading * (output.len as isize - 1),
+                    step: heading,
+                }
+            });
+        });
+        hits
+    }
+
+    /// How many hits there are in `grid`, without collecting them.
+    pub fn count(&self, grid: &Grid) -> usize {
+        let mut count = 0;
+        self.walk(grid, |_, _, _| count += 1);
+        count
+    }
+
+    /// Run the automaton along every line of `grid`, calling `on_hit` with the point each word
+    /// ends on, the heading the line was read in, and the word.
+    fn walk(&self, grid: &Grid, mut on_hit: impl FnMut(Point, Point, &Output)) {
+        for heading in LINE_HEADINGS {
+            // A line starts wherever the previous point along it is off the grid
+            for start in grid.points().filter(|p| !grid.contains(*p - heading)) {
+                let mut node = 0;
+                let mut point = start;
+                while grid.contains(point) {
+                    node = self.transitions[node][grid.get_point(point) as usize] as usize;
+                    for output in &self.outputs[node] {
+                        on_hit(point, heading, output);
+                    }
+                    point += heading;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::ALL_DIRECTIONS;
+
+    const EXAMPLE: &[u8] = b"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    /// Check every start and direction for every word.
+    fn brute_force(words: &[&[u8]], grid: &Grid) -> Vec<Hit> {
+        let mut hits = vec![];
+        for (word, text) in words.iter().enumerate() {
+            for start in grid.points() {
+                for step in ALL_DIRECTIONS {
+                    let found = text.iter().enumerate().all(|(i, byte)| {
+                        let point = start + step * i as isize;
+                        grid.contains(point) && grid.get_point(point) == *byte
+                    });
+                    if found {
+                        hits.push(Hit { word, start, step });
+                    }
+                }
+            }
+        }
+        hits.sort();
+        hits
+    }
+
+    #[test]
+    fn test_xmas() -> Result<()> {
+        let grid = Grid::new(EXAMPLE)?;
+        let search = WordSearch::new([b"XMAS"])?;
+        assert_eq!(search.count(&grid), 18);
+        let mut hits = search.find_all(&grid);
+        hits.sort();
+        assert_eq!(hits, brute_force(&[b"XMAS"], &grid));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlapping_words() -> Result<()> {
+        let grid = Grid::new(EXAMPLE)?;
+        let words: [&[u8]; 5] = [b"XMAS", b"MAS", b"AS", b"SAMX", b"MMS"];
+        let search = WordSearch::new(words)?;
+        let mut hits = search.find_all(&grid);
+        hits.sort();
+        assert_eq!(hits, brute_force(&words, &grid));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hit_position() -> Result<()> {
+        let grid = Grid::new(b"CAT\n..A\n..C\n")?;
+        let search = WordSearch::new(["CAT"])?;
+        let mut hits = search.find_all(&grid);
+        hits.sort();
+        assert_eq!(
+            hits,
+            vec![
+                Hit {
+                    word: 0,
+                    start: Point::new(0, 2),
+                    step: RIGHT,
+                },
+                Hit {
+                    word: 0,
+                    start: Point::new(2, 0),
+                    step: UP,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_palindrome_found_once_per_line() -> Result<()> {
+        let grid = Grid::new(b"ABA\n")?;
+        let search = WordSearch::new(["ABA"])?;
+        assert_eq!(search.count(&grid), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(WordSearch::new(Vec::<&[u8]>::new()).is_err());
+        assert!(WordSearch::new(["XMAS", ""]).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use {{crate_name}}::part2::process;
 use anyhow::Context;
+use aoclib::input::map_file;
 use clap::Parser;
 
 #[derive(Parser, Debug)]
@@ -12,9 +13,10 @@ pub struct Args {
 #[tracing::instrument]
 fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
-    let _args = Args::parse();
+    let args = Args::parse();
 
-    let result = process(include_bytes!("../../input2.txt")).context("process part 2")?;
+    let input = map_file(&args.input)?;
+    let result = process(&input).context("process part 2")?;
     println!("{}", result);
     Ok(())
 }
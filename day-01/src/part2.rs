@@ -1,17 +1,15 @@
-use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 use anyhow::Context;
+use aoclib::counter::Counter;
 
-pub fn naive_parse_file<P: AsRef<Path>>(
-    file: P,
-) -> anyhow::Result<(Vec<usize>, HashMap<usize, usize>)> {
+pub fn naive_parse_file<P: AsRef<Path>>(file: P) -> anyhow::Result<(Vec<usize>, Counter<usize>)> {
     let reader = BufReader::new(File::open(file)?);
     let mut left = vec![];
-    let mut map = HashMap::default();
+    let mut counts = Counter::new();
     for line in reader.lines() {
         let line = line?;
         let first = line
@@ -24,10 +22,10 @@ pub fn naive_parse_file<P: AsRef<Path>>(
             .next()
             .context("Failed to parse second number")?
             .parse::<usize>()?;
-        *map.entry(second).or_insert(0) += 1;
+        counts.add(second);
         left.push(first);
     }
-    Ok((left, map))
+    Ok((left, counts))
 }
 
 #[tracing::instrument]
@@ -35,7 +33,7 @@ pub fn process<P: AsRef<Path> + Debug>(input: P) -> anyhow::Result<String> {
     let (left_side, right_side_counts) = naive_parse_file(input)?;
     let ret = left_side
         .into_iter()
-        .map(|left| right_side_counts.get(&left).unwrap_or(&0) * left)
+        .map(|left| right_side_counts.get(&left) * left)
         .sum::<usize>()
         .to_string();
     Ok(ret)
@@ -7,10 +7,15 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    part1::process(divan::black_box("./input1.txt")).unwrap();
+    part1::process(divan::black_box(include_bytes!("../input1.txt"))).unwrap();
 }
 
 #[divan::bench]
 fn part2() {
-    part2::process(divan::black_box("./input2.txt")).unwrap();
+    part2::process(divan::black_box(include_bytes!("../input2.txt"))).unwrap();
+}
+
+#[divan::bench]
+fn part2_retrying() {
+    part2::process_retrying(divan::black_box(include_bytes!("../input2.txt"))).unwrap();
 }
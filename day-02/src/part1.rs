@@ -1,8 +1,8 @@
 use std::cmp::Ordering;
-use std::fmt::Debug;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+
+use aoclib::parsers::{expect_unsigned, ParseError};
+
+use crate::tolerance::removals_to_make_safe;
 
 pub struct SafetyReport {
     levels: Vec<usize>,
@@ -10,6 +10,9 @@ pub struct SafetyReport {
 }
 
 impl SafetyReport {
+    /// Retry the whole check once per level removed.
+    ///
+    /// Kept as a baseline for [`removals_to_make_safe`].
     pub fn make_safe(&self) -> Safety {
         if self.safety == Safety::Safe {
             return Safety::Safe;
@@ -101,20 +104,75 @@ impl Direction {
     }
 }
 
+/// Parse reports of space separated levels, one per line. Blank lines and spaces at the end of a
+/// line are skipped. Once an error is returned the iterator is exhausted.
+pub fn parse_reports(data: &[u8]) -> impl Iterator<Item = Result<Vec<u64>, ParseError>> + '_ {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        while let Some(end) = line_end_at(data, offset) {
+            offset += end;
+        }
+        if offset >= data.len() {
+            return None;
+        }
+
+        let mut levels = vec![];
+        loop {
+            let (level, end) = match expect_unsigned::<u64>(data, offset, "a level") {
+                Ok(parsed) => parsed,
+                Err(err) => {
+                    offset = data.len();
+                    return Some(Err(err));
+                }
+            };
+            offset = end;
+            levels.push(level);
+
+            let spaces = data[offset..].iter().take_while(|b| **b == b' ').count();
+            if let Some(end) = line_end_at(data, offset + spaces) {
+                offset += spaces + end;
+                break;
+            }
+            match data.get(offset + spaces) {
+                None => {
+                    offset = data.len();
+                    break;
+                }
+                // One space between levels, and parsing the next level catches any more
+                _ if spaces > 0 => offset += 1,
+                Some(byte) => {
+                    let err = ParseError::new(
+                        data,
+                        offset,
+                        format!(
+                            "expected a space or a newline, found `{}`",
+                            byte.escape_ascii()
+                        ),
+                    );
+                    offset = data.len();
+                    return Some(Err(err));
+                }
+            }
+        }
+        Some(Ok(levels))
+    })
+}
+
+/// The length of the `\n` or `\r\n` at `offset`, if there is one.
+fn line_end_at(data: &[u8], offset: usize) -> Option<usize> {
+    match data.get(offset..)? {
+        [b'\n', ..] => Some(1),
+        [b'\r', b'\n', ..] => Some(2),
+        _ => None,
+    }
+}
+
 #[tracing::instrument]
-pub fn process<P: AsRef<Path> + Debug>(input: P) -> anyhow::Result<String> {
-    let reader = BufReader::new(File::open(input)?);
+pub fn process(input: &[u8]) -> anyhow::Result<String> {
     let mut count_safe_reports = 0;
-    for line in reader.lines() {
-        let line = line?;
-        let level_iter = line.split_ascii_whitespace().map(|num| {
-            num.parse::<usize>()
-                .expect("Failed to parse int from level")
-        });
-        let safety = Safety::check_safety(level_iter);
-        match safety.safety {
-            Safety::Safe => count_safe_reports += 1,
-            Safety::Unsafe => (),
+    for levels in parse_reports(input) {
+        if removals_to_make_safe(&levels?, 0).is_some() {
+            count_safe_reports += 1;
         }
     }
     Ok(count_safe_reports.to_string())
@@ -123,28 +181,50 @@ pub fn process<P: AsRef<Path> + Debug>(input: P) -> anyhow::Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use std::path::Path;
-    use tempdir::TempDir;
-
-    fn write_to_file<P: AsRef<Path>>(data: &str, file: P) {
-        let mut file = std::fs::File::create(file).unwrap();
-        file.write_all(data.as_bytes()).unwrap();
-    }
 
     #[test]
     fn test_process() -> anyhow::Result<()> {
-        let input = "7 6 4 2 1
+        let input = b"7 6 4 2 1
 1 2 7 8
 9 7 6 2 1
 1 3 2 4 5
 8 6 4 4 1
 1 3 6 7 9";
-        let dir = TempDir::new("test")?;
-        let file = dir.path().join("input1.txt");
-        write_to_file(input, &file);
 
-        assert_eq!("2", process(&file)?);
+        assert_eq!("2", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_reports() -> anyhow::Result<()> {
+        let reports = parse_reports(b"7 6 4\n10\n").collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(reports, vec![vec![7, 6, 4], vec![10]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_reports_crlf() -> anyhow::Result<()> {
+        let reports = parse_reports(b"7 6 4\r\n10\r\n").collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(reports, vec![vec![7, 6, 4], vec![10]]);
+        assert!(parse_reports(b"7 6\r4\n").any(|report| report.is_err()));
         Ok(())
     }
+
+    #[test]
+    fn test_parse_reports_blank_lines_and_trailing_spaces() -> anyhow::Result<()> {
+        let reports = parse_reports(b"7 6 4 \n\n10  \r\n\n").collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(reports, vec![vec![7, 6, 4], vec![10]]);
+        let reports = parse_reports(b"\n1 2   ").collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(reports, vec![vec![1, 2]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_report() {
+        let input = b"7 6 4 2 1
+1 2  7 8";
+        let err = process(input).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 5));
+    }
 }
@@ -1,24 +1,34 @@
-use std::fmt::Debug;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
-use crate::part1::Safety;
+use crate::part1::{parse_reports, Safety};
+use crate::tolerance::removals_to_make_safe;
 
 #[tracing::instrument]
-pub fn process<P: AsRef<Path> + Debug>(input: P) -> anyhow::Result<String> {
-    let reader = BufReader::new(File::open(input)?);
+pub fn process(input: &[u8]) -> anyhow::Result<String> {
     let mut count_safe_reports = 0;
-    for line in reader.lines() {
-        let line = line?;
-        let level_iter = line.split_ascii_whitespace().map(|num| {
-            num.parse::<usize>()
-                .expect("Failed to parse int from level")
-        });
-        let safety_report = Safety::check_safety(level_iter);
-        match safety_report.make_safe() {
-            Safety::Safe => count_safe_reports += 1,
-            Safety::Unsafe => (),
+    for (report, levels) in parse_reports(input).enumerate() {
+        let levels = levels?;
+        // The Problem Dampener can take out a single level
+        if let Some(removals) = removals_to_make_safe(&levels, 1) {
+            for removed in removals {
+                tracing::debug!(
+                    "report {} is safe without level {removed} ({})",
+                    report + 1,
+                    levels[removed]
+                );
+            }
+            count_safe_reports += 1;
+        }
+    }
+    Ok(count_safe_reports.to_string())
+}
+
+/// [`process`] by retrying each report once per level, kept as a baseline for
+/// [`removals_to_make_safe`].
+pub fn process_retrying(input: &[u8]) -> anyhow::Result<String> {
+    let mut count_safe_reports = 0;
+    for levels in parse_reports(input) {
+        let report = Safety::check_safety(levels?.into_iter().map(|level| level as usize));
+        if report.make_safe() == Safety::Safe {
+            count_safe_reports += 1;
         }
     }
     Ok(count_safe_reports.to_string())
@@ -27,28 +37,18 @@ pub fn process<P: AsRef<Path> + Debug>(input: P) -> anyhow::Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use std::path::Path;
-    use tempdir::TempDir;
-
-    fn write_to_file<P: AsRef<Path>>(data: &str, file: P) {
-        let mut file = std::fs::File::create(file).unwrap();
-        file.write_all(data.as_bytes()).unwrap();
-    }
 
     #[test]
     fn test_process() -> anyhow::Result<()> {
-        let input = "7 6 4 2 1
+        let input = b"7 6 4 2 1
 1 2 7 8 9
 9 7 6 2 1
 1 3 2 4 5
 8 6 4 4 1
 1 3 6 7 9";
-        let dir = TempDir::new("test")?;
-        let file = dir.path().join("input2.txt");
-        write_to_file(input, &file);
 
-        assert_eq!("4", process(&file)?);
+        assert_eq!("4", process(input)?);
+        assert_eq!("4", process_retrying(input)?);
         Ok(())
     }
 }
@@ -0,0 +1,148 @@
+/// Whether `next` can follow `prev` in a safe report going up when `increasing`, else down.
+#[inline]
+fn is_safe_step(prev: u64, next: u64, increasing: bool) -> bool {
+    let diff = if increasing {
+        next.checked_sub(prev)
+    } else {
+        prev.checked_sub(next)
+    };
+    diff.is_some_and(|diff| (1..=3).contains(&diff))
+}
+
+/// The fewest levels to remove, by index, so `levels` is safe, as long as that's at most
+/// `max_removals`.
+///
+/// An already safe report needs no removals, so gives an empty list. Runs in
+/// `O(levels.len() * max_removals^2)`, so linear for the puzzle's one removal.
+pub fn removals_to_make_safe(levels: &[u64], max_removals: usize) -> Option<Vec<usize>> {
+    let up = removals_in_direction(levels, max_removals, true);
+    let down = removals_in_direction(levels, max_removals, false);
+    match (up, down) {
+        (Some(up), Some(down)) if down.len() < up.len() => Some(down),
+        (Some(up), _) => Some(up),
+        (None, down) => down,
+    }
+}
+
+fn removals_in_direction(
+    levels: &[u64],
+    max_removals: usize,
+    increasing: bool,
+) -> Option<Vec<usize>> {
+    let n = levels.len();
+    if n == 0 {
+        return Some(vec![]);
+    }
+    let k = max_removals;
+    // prev_kept[at(i, j)] is set if some safe run ends by keeping level i after j removals, and
+    // holds the level kept before i, or None if i is the first one kept
+    let at = |i: usize, j: usize| i * (k + 1) + j;
+    let mut prev_kept: Vec<Option<Option<usize>>> = vec![None; n * (k + 1)];
+    for i in 0..n {
+        if i <= k {
+            // Remove everything before i
+            prev_kept[at(i, i)] = Some(None);
+        }
+        // Keep i straight after prev, removing the `skipped` levels in between
+        for skipped in 0..i.min(k + 1) {
+            let prev = i - 1 - skipped;
+            if !is_safe_step(levels[prev], levels[i], increasing) {
+                continue;
+            }
+            for j in skipped..=k {
+                if prev_kept[at(i, j)].is_none() && prev_kept[at(prev, j - skipped)].is_some() {
+                    prev_kept[at(i, j)] = Some(Some(prev));
+                }
+            }
+        }
+    }
+
+    // The last level kept, and the removals up to it, with the fewest removals overall
+    let (mut last, mut removed) = (0..n)
+        .flat_map(|i| (0..=k).map(move |j| (i, j)))
+        .filter(|(i, j)| prev_kept[at(*i, *j)].is_some() && j + (n - 1 - i) <= k)
+        .min_by_key(|(i, j)| (j + (n - 1 - i), *i))?;
+
+    let mut kept = vec![false; n];
+    loop {
+        kept[last] = true;
+        let Some(prev) = prev_kept[at(last, removed)].expect("only reachable states are followed")
+        else {
+            break;
+        };
+        removed -= last - prev - 1;
+        last = prev;
+    }
+    Some((0..n).filter(|i| !kept[*i]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::{Safety, SafetyReport};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[7, 6, 4, 2, 1], 0, Some(vec![]))]
+    #[case(&[1, 2, 7, 8, 9], 0, None)]
+    #[case(&[1, 2, 7, 8, 9], 1, None)]
+    #[case(&[9, 7, 6, 2, 1], 1, None)]
+    #[case(&[1, 3, 2, 4, 5], 1, Some(vec![1]))]
+    #[case(&[8, 6, 4, 4, 1], 1, Some(vec![2]))]
+    #[case(&[1, 3, 6, 7, 9], 1, Some(vec![]))]
+    #[case(&[50, 1, 2, 3], 1, Some(vec![0]))]
+    #[case(&[1, 2, 3, 50], 1, Some(vec![3]))]
+    #[case(&[1, 2, 3, 50, 60, 4], 1, None)]
+    #[case(&[1, 2, 3, 50, 60, 4], 2, Some(vec![3, 4]))]
+    #[case(&[5], 0, Some(vec![]))]
+    #[case(&[], 0, Some(vec![]))]
+    #[case(&[5, 5], 0, None)]
+    #[case(&[5, 5], 1, Some(vec![1]))]
+    fn test_removals(
+        #[case] levels: &[u64],
+        #[case] max_removals: usize,
+        #[case] expected: Option<Vec<usize>>,
+    ) {
+        assert_eq!(removals_to_make_safe(levels, max_removals), expected);
+    }
+
+    /// Every report of `len` levels from `0..max`.
+    fn all_reports(len: usize, max: u64) -> Vec<Vec<u64>> {
+        (0..len).fold(vec![vec![]], |reports, _| {
+            reports
+                .into_iter()
+                .flat_map(|report| {
+                    (0..max).map(move |level| {
+                        let mut report = report.clone();
+                        report.push(level);
+                        report
+                    })
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_matches_retrying() {
+        for levels in all_reports(5, 7) {
+            let report: SafetyReport = Safety::check_safety(levels.iter().map(|l| *l as usize));
+            let removals = removals_to_make_safe(&levels, 1);
+            assert_eq!(
+                removals.is_some(),
+                report.make_safe() == Safety::Safe,
+                "{levels:?}"
+            );
+            if let Some(removals) = removals {
+                let kept: Vec<_> = (0..levels.len())
+                    .filter(|i| !removals.contains(i))
+                    .map(|i| levels[i])
+                    .collect();
+                assert!(
+                    kept.windows(2).all(|w| is_safe_step(w[0], w[1], true))
+                        || kept.windows(2).all(|w| is_safe_step(w[0], w[1], false)),
+                    "{levels:?} {removals:?}"
+                );
+            }
+        }
+    }
+}
@@ -8,15 +8,22 @@ fn main() {
 #[divan::bench]
 fn part1() {
     part1::process(divan::black_box(
-        "../input1.txt",
+        include_bytes!("../input1.txt"),
     ))
     .unwrap();
 }
 
+#[divan::bench]
+fn part1_windows() {
+    part1::Grid::new(divan::black_box(include_bytes!("../input1.txt")))
+        .unwrap()
+        .count_occurances(b"XMAS");
+}
+
 #[divan::bench]
 fn part2() {
     part2::process(divan::black_box(
-        "../input2.txt",
+        include_bytes!("../input2.txt"),
     ))
     .unwrap();
 }
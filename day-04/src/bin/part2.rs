@@ -1,5 +1,6 @@
 use day_04::part2::process;
 use anyhow::Context;
+use aoclib::input::map_file;
 use clap::Parser;
 
 #[derive(Parser, Debug)]
@@ -14,7 +15,8 @@ fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
     let args = Args::parse();
 
-    let result = process(&args.input).context("process part 2")?;
+    let input = map_file(&args.input)?;
+    let result = process(&input).context("process part 2")?;
     println!("{}", result);
     Ok(())
 }
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use aoclib::word_search::WordSearch;
 
 use itertools::Itertools;
 
@@ -65,21 +66,21 @@ impl Grid {
         self.data[index]
     }
 
-    pub fn rows(&self) -> ViewIter {
+    pub fn rows(&self) -> ViewIter<'_> {
         Box::new(
             (0..self.height)
                 .map(move |y| Box::new((0..self.width).map(move |x| Point { x, y })) as PointIter),
         )
     }
 
-    pub fn columns(&self) -> ViewIter {
+    pub fn columns(&self) -> ViewIter<'_> {
         Box::new(
             (0..self.width)
                 .map(move |x| Box::new((0..self.height).map(move |y| Point { x, y })) as PointIter),
         )
     }
 
-    pub fn left_diagonals(&self) -> ViewIter {
+    pub fn left_diagonals(&self) -> ViewIter<'_> {
         let width = self.width;
         let height = self.height;
 
@@ -120,7 +121,7 @@ impl Grid {
         Box::new(from_rows.chain(from_cols))
     }
 
-    pub fn right_diagonals(&self) -> ViewIter {
+    pub fn right_diagonals(&self) -> ViewIter<'_> {
         let width = self.width;
         let height = self.height;
 
@@ -155,7 +156,7 @@ impl Grid {
         Box::new(from_rows.chain(from_cols))
     }
 
-    pub fn all_views(&self) -> ViewIter {
+    pub fn all_views(&self) -> ViewIter<'_> {
         Box::new(
             self.rows()
                 .chain(self.columns())
@@ -164,7 +165,9 @@ impl Grid {
         )
     }
 
-    /// Search the grid for anny occurances of the needle
+    /// Search the grid for anny occurances of the needle, one window at a time.
+    ///
+    /// Kept as a baseline for [`aoclib::word_search::WordSearch`].
     pub fn count_occurances(&self, needle: &[u8]) -> usize {
         let mut buffer = Vec::with_capacity(self.height * self.width);
         let rev_needle = needle.iter().copied().rev().collect::<Vec<_>>();
@@ -196,8 +199,8 @@ pub fn find_all(needle: &[u8], rev_needle: &[u8], haystack: &[u8]) -> usize {
 #[tracing::instrument]
 pub fn process(input: &'static [u8]) -> anyhow::Result<String> {
     // Find the number of occurances of XMAS in the grid.
-    let grid = Grid::new(input)?;
-    let count = grid.count_occurances(b"XMAS");
+    let grid = aoclib::grid::Grid::new(input)?;
+    let count = WordSearch::new([b"XMAS"])?.count(&grid);
     Ok(count.to_string())
 }
 
@@ -205,13 +208,6 @@ pub fn process(input: &'static [u8]) -> anyhow::Result<String> {
 mod tests {
     use super::*;
     use anyhow::Result;
-    use std::io::Write;
-    use std::path::Path;
-
-    fn write_to_file<P: AsRef<Path>>(data: &str, file: P) {
-        let mut file = std::fs::File::create(file).unwrap();
-        file.write_all(data.as_bytes()).unwrap();
-    }
 
     #[test]
     fn test_process() -> Result<()> {
@@ -229,6 +225,7 @@ MXMXAXMASX";
         // let count = grid.count_occurances(b"XMAS");
         // assert_eq!(18, count);
         assert_eq!("18", process(input)?);
+        assert_eq!(18, Grid::new(input)?.count_occurances(b"XMAS"));
         Ok(())
     }
 
@@ -1,32 +1,47 @@
-use std::fmt::Debug;
-use std::path::Path;
+use aoclib::{grid::Grid, pattern::Pattern};
+
+/// An `X` of two `MAS`, which can be read either way along both diagonals.
+const X_MAS: &[u8] = b"M.S
+.A.
+M.S";
 
 #[tracing::instrument]
-pub fn process<P: AsRef<Path> + Debug>(_input: P) -> anyhow::Result<String> {
-    todo!("day 01 - part 2");
+pub fn process(input: &[u8]) -> anyhow::Result<String> {
+    let grid = Grid::new(input)?;
+    let count = Pattern::new(X_MAS, b'.')?
+        .find_all_orientations(&grid)
+        .len();
+    Ok(count.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use std::path::Path;
-    use tempdir::TempDir;
 
-    fn write_to_file<P: AsRef<Path>>(data: &str, file: P) {
-        let mut file = std::fs::File::create(file).unwrap();
-        file.write_all(data.as_bytes()).unwrap();
+    #[test]
+    fn test_process() -> anyhow::Result<()> {
+        let input = b"MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+        assert_eq!("9", process(input)?);
+        Ok(())
     }
 
     #[test]
-    fn test_process() -> anyhow::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        let dir = TempDir::new("test")?;
-        let file = dir.path().join("input2.txt");
-        write_to_file(input, &file);
+    fn test_plus_shape_is_not_an_x() -> anyhow::Result<()> {
+        let input = b".M.
+MAS
+.S.";
 
-        assert_eq!("", process(&file)?);
+        assert_eq!("0", process(input)?);
         Ok(())
     }
 }
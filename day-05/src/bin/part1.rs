@@ -1,4 +1,5 @@
 use anyhow::Context;
+use aoclib::input::map_file;
 use clap::Parser;
 use day_05::part1::process;
 
@@ -12,9 +13,10 @@ pub struct Args {
 #[tracing::instrument]
 fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
-    let _args = Args::parse();
+    let args = Args::parse();
 
-    let result = process(include_bytes!("../../input1.txt")).context("process part 1")?;
+    let input = map_file(&args.input)?;
+    let result = process(&input).context("process part 1")?;
     println!("{}", result);
     Ok(())
 }
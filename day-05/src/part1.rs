@@ -1,147 +1,180 @@
+use aoclib::{
+    bitset::BitSet,
+    parsers::{expect_bytes, expect_unsigned, ParseError},
+    toposort::{toposort, Cycle},
+};
 use itertools::Itertools;
+use rustc_hash::FxHashMap;
 
-type After = u8;
-type Before = u8;
-type Afters = u128;
-// type Befores = u128; // We never actually look at it in this way
+pub type Page = u32;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Rules {
-    // The index into the lookup corresponds to the page number, so the value at index 42 is the bitset of pages that have a rule that specifies that 42 must come before them.
-    forward_lookup: [Afters; 100],
-    // The index into the lookup corresponds to the page number, so the value at index 42 is the bitset of pages that have a rule that specifies that 42 must come after them.
-    // backward_lookup: [Befores; 100],
+    // Pages are interned to dense indices in the order they're first seen in a rule
+    index: FxHashMap<Page, usize>,
+    pages: Vec<Page>,
+    // The index into the lookup is the page's index, so the set at index 42 is the indices of
+    // pages that have a rule that specifies that page 42 must come before them.
+    forward_lookup: Vec<BitSet>,
 }
 
 impl Rules {
     pub fn new() -> Self {
-        Self {
-            forward_lookup: [0; 100],
-            // backward_lookup: [0; 100],
-        }
+        Self::default()
     }
 
-    pub fn add_rule(&mut self, before: Before, after: After) {
-        self.forward_lookup[before as usize] |= 1u128 << after;
-        // self.backward_lookup[after as usize] |= 1u128 << before;
+    /// The dense index of `page`, or None if no rule mentions it.
+    pub fn index_of(&self, page: Page) -> Option<usize> {
+        self.index.get(&page).copied()
     }
 
-    pub fn is_valid_page_update_set(&self, updates: &[(u8, u128)]) -> bool {
-        for (page, pages_before_this_page) in updates {
-            // are there any pages before this page that were supposed to come after it instead?
-            let values_that_should_come_after_this_page = self.forward_lookup[*page as usize];
-            if (*pages_before_this_page & values_that_should_come_after_this_page) > 0 {
-                return false;
-            }
-        }
-        true
+    fn intern(&mut self, page: Page) -> usize {
+        let next = self.index.len();
+        *self.index.entry(page).or_insert_with(|| {
+            self.pages.push(page);
+            self.forward_lookup.push(BitSet::new());
+            next
+        })
     }
 
-    pub fn correct_update_set(&self, updates: &mut [(u8, u128)]) {
-        loop {
-            let mut right_index = 0;
-            let mut left_index = 0;
-            for (i, (page, pages_before_this_page)) in updates.iter().enumerate() {
-                let values_that_should_come_after_this_page = self.forward_lookup[*page as usize];
-                let bad_values = *pages_before_this_page & values_that_should_come_after_this_page;
-                let smallest_bad_value = bad_values.trailing_zeros() as u8;
-                if (bad_values) > 0 {
-                    // We've found something wrong! There's a value before this number that should come after it.
-                    right_index = i;
-                    left_index = updates
-                        .iter()
-                        .find_position(|(page_num, _)| *page_num == smallest_bad_value)
-                        .unwrap()
-                        .0;
-                    break;
-                }
-            }
-
-            if right_index == left_index {
-                // We are done maybe?
-                break;
-            }
+    pub fn add_rule(&mut self, before: Page, after: Page) {
+        let before = self.intern(before);
+        let after = self.intern(after);
+        self.forward_lookup[before].insert(after);
+    }
 
-            // Swap the bad values and rebuild the indexes
-            updates.swap(right_index, left_index);
-            let mut values_before = 0;
-            for update in updates.iter_mut() {
-                update.1 = values_before;
-                values_before |= 1u128 << update.0;
+    /// The position of a page that comes after `update[i]` but should come before it, if any.
+    ///
+    /// Pages that no rule mentions can go anywhere.
+    fn find_bad_pair(&self, update: &[Page]) -> Option<(usize, usize)> {
+        let mut pages_before_this_page = BitSet::with_capacity(self.index.len());
+        for (i, page) in update.iter().enumerate() {
+            let Some(index) = self.index_of(*page) else {
+                continue;
+            };
+            // are there any pages before this page that were supposed to come after it instead?
+            let values_that_should_come_after_this_page = &self.forward_lookup[index];
+            if let Some(bad_value) = pages_before_this_page
+                .intersection(values_that_should_come_after_this_page)
+                .next()
+            {
+                let left_index = update
+                    .iter()
+                    .find_position(|page| self.index_of(**page) == Some(bad_value))
+                    .unwrap()
+                    .0;
+                return Some((left_index, i));
             }
+            pages_before_this_page.insert(index);
         }
+        None
     }
-}
 
-impl Default for Rules {
-    fn default() -> Self {
-        Self::new()
+    pub fn is_valid_page_update_set(&self, update: &[Page]) -> bool {
+        self.find_bad_pair(update).is_none()
+    }
+
+    /// Reorder `update` to follow the rules that apply to its pages, or find the rules that
+    /// contradict each other.
+    ///
+    /// Pages that no rule orders relative to each other keep their order, and a page listed twice
+    /// is kept both times.
+    pub fn correct_update_set(&self, update: &[Page]) -> Result<Vec<Page>, Cycle<Page>> {
+        // Sort positions rather than pages, so repeated pages aren't merged into one node
+        let ruled: Vec<(usize, usize)> = update
+            .iter()
+            .enumerate()
+            .filter_map(|(i, page)| Some((i, self.index_of(*page)?)))
+            .collect();
+        let edges = ruled.iter().flat_map(|&(i, before)| {
+            ruled
+                .iter()
+                .filter(move |&&(j, after)| j != i && self.forward_lookup[before].contains(after))
+                .map(move |&(j, _)| (i, j))
+        });
+        let to_pages = |positions: Vec<usize>| positions.into_iter().map(|i| update[i]).collect();
+        toposort(0..update.len(), edges)
+            .map(to_pages)
+            .map_err(|cycle| Cycle {
+                nodes: to_pages(cycle.nodes),
+            })
     }
 }
 
 type RulesEndOffset = usize;
 
-pub fn parse_rules(data: &[u8]) -> (Rules, RulesEndOffset) {
+/// Parse a page number at `offset`, returning it and the offset just past it.
+fn parse_page(data: &[u8], offset: usize) -> Result<(Page, usize), ParseError> {
+    expect_unsigned::<Page>(data, offset, "a page number")
+}
+
+pub fn parse_rules(data: &[u8]) -> Result<(Rules, RulesEndOffset), ParseError> {
     let mut rules = Rules::new();
     let mut offset = 0;
-    while data[offset] != b'\n' {
-        let mut before = 0;
-        let mut after = 0;
-        // First two bytes are before
-        before += data[offset] - 48;
-        before = (before * 10) + (data[offset + 1] - 48);
-        // skip third byte
-        // Four and five are after
-        after += data[offset + 3] - 48;
-        after = (after * 10) + (data[offset + 4] - 48);
+    // Rules are `before|after` lines, ending with a blank line
+    while data.get(offset) != Some(&b'\n') {
+        let (before, end) = parse_page(data, offset)?;
+        offset = expect_bytes(data, end, b"|")?;
+        let (after, end) = parse_page(data, offset)?;
+        offset = expect_bytes(data, end, b"\n")?;
         rules.add_rule(before, after);
-        // sixth is newline
-        offset += 6
     }
-    (rules, offset)
+    Ok((rules, offset))
 }
 
-pub fn parse_pages(data: &[u8]) -> impl Iterator<Item = Vec<(u8, u128)>> + '_ {
-    let mut offset = 0;
+/// Parse the comma separated page updates, starting at `offset` in `data`.
+///
+/// Parsing stops at the end of the data or at a blank line. Once an error is returned the
+/// iterator is exhausted.
+pub fn parse_pages(
+    data: &[u8],
+    mut offset: usize,
+) -> impl Iterator<Item = Result<Vec<Page>, ParseError>> + '_ {
     std::iter::from_fn(move || {
-        let mut pages_before_this_page = 0;
-        let mut page_update = vec![];
+        if offset >= data.len() || data[offset] == b'\n' {
+            return None;
+        }
 
-        while offset < data.len() && data[offset] != b'\n' {
-            match data[offset] {
-                b'0'..=b'9' => {
-                    // this byte and the next one are digits
-                    let mut num = 0;
-                    num += data[offset] - 48;
-                    num = (num * 10) + (data[offset + 1] - 48);
-                    offset += 2;
-                    page_update.push((num, pages_before_this_page));
-                    pages_before_this_page |= 1u128 << num as u128;
+        let mut page_update = vec![];
+        loop {
+            let (num, end) = match parse_page(data, offset) {
+                Ok(parsed) => parsed,
+                Err(err) => {
+                    offset = data.len();
+                    return Some(Err(err));
                 }
-                b',' => {
-                    offset += 1;
+            };
+            offset = end;
+            page_update.push(num);
+
+            match data.get(offset) {
+                Some(b',') => offset += 1,
+                Some(b'\n') | None => break,
+                Some(byte) => {
+                    let err = ParseError::new(
+                        data,
+                        offset,
+                        format!("expected `,` or a newline, found `{}`", byte.escape_ascii()),
+                    );
+                    offset = data.len();
+                    return Some(Err(err));
                 }
-                _ => unreachable!(),
             }
         }
         offset += 1; // advance past newline
-        if page_update.is_empty() {
-            None
-        } else {
-            Some(page_update)
-        }
+        Some(Ok(page_update))
     })
 }
 
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    let (rules, rules_end_offset) = parse_rules(input);
+    let (rules, rules_end_offset) = parse_rules(input)?;
     // Sum up the middle page of page update sets that are valid
     let mut answer = 0;
-    for page_updates in parse_pages(&input[rules_end_offset + 1..]) {
+    for page_updates in parse_pages(input, rules_end_offset + 1) {
+        let page_updates = page_updates?;
         if rules.is_valid_page_update_set(&page_updates) {
-            let middle = page_updates[page_updates.len() / 2];
-            answer += middle.0 as u32;
+            answer += page_updates[page_updates.len() / 2] as u64;
         }
     }
     Ok(answer.to_string())
@@ -185,4 +218,115 @@ mod tests {
         assert_eq!("143", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_update() {
+        let input = b"47|53
+97|13
+
+75,47,61
+97,6x,53";
+        let err = process(input).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (5, 5));
+        assert_eq!(err.message, "expected `,` or a newline, found `x`");
+    }
+
+    #[test]
+    fn test_arbitrary_page_numbers() -> anyhow::Result<()> {
+        let input = b"7|130
+130|4000
+1000000|7
+
+7,130,4000
+4000,130
+1000000,5,7,130
+130,7
+4000,7
+";
+        // Pages only in updates have no rules, so they can go anywhere, and rules aren't
+        // transitive, so 4000 can come before 7
+        assert_eq!("144", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_page_number_too_large() {
+        let input = b"7|130
+
+7,99999999999,130";
+        let err = process(input).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 3));
+    }
+
+    #[test]
+    fn test_correct_update_set() -> anyhow::Result<()> {
+        let input = b"47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+";
+        let (rules, _) = parse_rules(&[input.as_slice(), b"\n"].concat())?;
+        assert_eq!(
+            rules.correct_update_set(&[75, 97, 47, 61, 53])?,
+            vec![97, 75, 47, 61, 53]
+        );
+        assert_eq!(rules.correct_update_set(&[61, 13, 29])?, vec![61, 29, 13]);
+        assert_eq!(
+            rules.correct_update_set(&[97, 13, 75, 29, 47])?,
+            vec![97, 75, 47, 29, 13]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_correct_update_set_cycle() -> anyhow::Result<()> {
+        let (rules, _) = parse_rules(b"1|2\n2|3\n3|1\n3|4\n\n")?;
+        assert_eq!(rules.correct_update_set(&[4, 2, 1])?, vec![4, 1, 2]);
+        let err = rules.correct_update_set(&[4, 3, 2, 1]).unwrap_err();
+        assert_eq!(err.nodes.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_correct_update_set_keeps_duplicates() -> anyhow::Result<()> {
+        let (rules, _) = parse_rules(b"1|2\n2|3\n\n")?;
+        assert_eq!(
+            rules.correct_update_set(&[3, 2, 7, 1, 3])?,
+            vec![7, 1, 2, 3, 3]
+        );
+        assert_eq!(rules.correct_update_set(&[5, 5])?, vec![5, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_rule() {
+        let input = b"47|53
+97-13
+
+75,47,61";
+        let err = process(input).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "expected `|`, found `-` at line 2, column 3\n    97-13\n      ^"
+        );
+    }
 }
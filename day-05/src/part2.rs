@@ -2,14 +2,14 @@ use crate::part1::{parse_pages, parse_rules};
 
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    let (rules, rules_end_offset) = parse_rules(input);
+    let (rules, rules_end_offset) = parse_rules(input)?;
     // Sum up the middle page of page update sets that are invalid once they have been corrected
     let mut answer = 0;
-    for mut page_updates in parse_pages(&input[rules_end_offset + 1..]) {
+    for page_updates in parse_pages(input, rules_end_offset + 1) {
+        let page_updates = page_updates?;
         if !rules.is_valid_page_update_set(&page_updates) {
-            rules.correct_update_set(&mut page_updates);
-            let middle = page_updates[page_updates.len() / 2];
-            answer += middle.0 as u32;
+            let page_updates = rules.correct_update_set(&page_updates)?;
+            answer += page_updates[page_updates.len() / 2] as u64;
         }
     }
     Ok(answer.to_string())
@@ -53,4 +53,30 @@ mod tests {
         assert_eq!("123", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_arbitrary_page_numbers() -> anyhow::Result<()> {
+        let input = b"7|130
+130|4000
+1000000|7
+
+4000,7,130
+130,1000000,7
+";
+        // 7,130,4000 and 1000000,7,130
+        assert_eq!("137", process(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_contradictory_rules() {
+        let input = b"1|2
+2|3
+3|1
+
+1,2,3
+";
+        let err = process(input).unwrap_err();
+        assert!(err.to_string().starts_with("cycle in ordering: "), "{err}");
+    }
 }
@@ -14,3 +14,8 @@ fn part1() {
 fn part2() {
     part2::process(divan::black_box(include_bytes!("../input2.txt"))).unwrap();
 }
+
+#[divan::bench]
+fn part2_stepwise() {
+    part1::obstruct_the_guard_stepwise(divan::black_box(include_bytes!("../input2.txt"))).unwrap();
+}
@@ -0,0 +1,211 @@
+use aoclib::{
+    direction::Direction,
+    grid::{Grid, Point},
+};
+
+use crate::part1::{State, BLOCKER};
+
+/// Where the guard stops when walking in each direction from each cell, so they can jump
+/// straight from one turn to the next instead of walking one cell at a time.
+///
+/// Every cell has an entry, blockers included, which is where a guard would stop walking from
+/// that cell if it weren't a blocker. That's what lets a blocker be removed again.
+#[derive(Debug, Clone)]
+pub struct JumpTable {
+    width: usize,
+    height: usize,
+    blockers: Vec<bool>,
+    // The cell just before the next blocker, per cell and heading, or None to walk off the grid
+    stops: Vec<[Option<Point>; 4]>,
+}
+
+impl JumpTable {
+    pub fn new(grid: &Grid) -> Self {
+        let (width, height) = (grid.num_cols(), grid.num_rows());
+        let mut table = Self {
+            width,
+            height,
+            blockers: vec![false; width * height],
+            stops: vec![[None; 4]; width * height],
+        };
+        for point in grid.points() {
+            let i = table.index(point);
+            table.blockers[i] = grid.get_point(point) == BLOCKER;
+        }
+
+        // Sweep backwards from each edge, remembering the last blocker seen
+        for dir in Direction::iter() {
+            let step = dir.to_point();
+            for start in table.edge(dir) {
+                let mut stop = None;
+                let mut point = start;
+                while table.contains(point) {
+                    let i = table.index(point);
+                    table.stops[i][dir.index()] = stop;
+                    if table.blockers[i] {
+                        stop = Some(point - step);
+                    }
+                    point -= step;
+                }
+            }
+        }
+        table
+    }
+
+    /// Where the guard stops walking from `pos` in `dir`, or None if they walk off the grid.
+    #[inline]
+    pub fn stop(&self, pos: Point, dir: Direction) -> Option<Point> {
+        self.stops[self.index(pos)][dir.index()]
+    }
+
+    /// Walk to the next blocker and turn, or None if the guard walks off the grid.
+    #[inline]
+    pub fn jump(&self, (pos, dir): State) -> Option<State> {
+        self.stop(pos, dir).map(|stop| (stop, dir.turn_right()))
+    }
+
+    /// Floyd's tortoise and hare over whole jumps.
+    pub fn is_loop(&self, start: State) -> bool {
+        let mut turtle = start;
+        let mut bunny = start;
+        loop {
+            let (Some(next_turtle), Some(next_bunny)) = (
+                self.jump(turtle),
+                self.jump(bunny).and_then(|b| self.jump(b)),
+            ) else {
+                return false;
+            };
+            turtle = next_turtle;
+            bunny = next_bunny;
+            if turtle == bunny {
+                return true;
+            }
+        }
+    }
+
+    /// Add a blocker, only updating the cells in its row and column that now stop at it.
+    pub fn add_blocker(&mut self, blocker: Point) {
+        let i = self.index(blocker);
+        self.blockers[i] = true;
+        for dir in Direction::iter() {
+            self.set_stops_behind(blocker, dir, Some(blocker - dir.to_point()));
+        }
+    }
+
+    /// Undo [`JumpTable::add_blocker`].
+    pub fn remove_blocker(&mut self, blocker: Point) {
+        let i = self.index(blocker);
+        self.blockers[i] = false;
+        for dir in Direction::iter() {
+            // Everything that stopped at the blocker now walks on to wherever it would have
+            let stop = self.stops[i][dir.index()];
+            self.set_stops_behind(blocker, dir, stop);
+        }
+    }
+
+    /// Set the stop for every cell that walks into `blocker` heading in `dir`.
+    fn set_stops_behind(&mut self, blocker: Point, dir: Direction, stop: Option<Point>) {
+        let step = dir.to_point();
+        let mut point = blocker - step;
+        while self.contains(point) {
+            let i = self.index(point);
+            self.stops[i][dir.index()] = stop;
+            if self.blockers[i] {
+                break;
+            }
+            point -= step;
+        }
+    }
+
+    /// The cells on the edge the guard walks off of when heading in `dir`.
+    fn edge(&self, dir: Direction) -> Vec<Point> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        match dir {
+            Direction::Up => (0..width).map(|x| Point::new(x, height - 1)).collect(),
+            Direction::Down => (0..width).map(|x| Point::new(x, 0)).collect(),
+            Direction::Right => (0..height).map(|y| Point::new(width - 1, y)).collect(),
+            Direction::Left => (0..height).map(|y| Point::new(0, y)).collect(),
+        }
+    }
+
+    #[inline]
+    fn contains(&self, point: Point) -> bool {
+        (0..self.width as isize).contains(&point.x) && (0..self.height as isize).contains(&point.y)
+    }
+
+    #[inline]
+    fn index(&self, point: Point) -> usize {
+        point.y as usize * self.width + point.x as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::{find_guard, one_step, turtle_guard_and_bunny_guard};
+
+    const EXAMPLE: &[u8] = b"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    /// Walk one cell at a time until the next turn, or None if the guard walks off the grid.
+    fn walk_to_turn(grid: &Grid, (mut pos, dir): State) -> Option<State> {
+        loop {
+            let (next_pos, next_dir) = one_step(grid, pos, dir)?;
+            if next_dir != dir {
+                return Some((next_pos, next_dir));
+            }
+            pos = next_pos;
+        }
+    }
+
+    fn assert_matches_grid(table: &JumpTable, grid: &Grid) {
+        for pos in grid.points().filter(|p| grid.get_point(*p) != BLOCKER) {
+            for dir in Direction::iter() {
+                assert_eq!(
+                    table.jump((pos, dir)),
+                    walk_to_turn(grid, (pos, dir)),
+                    "{pos:?} {dir:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_jumps_match_walking() -> anyhow::Result<()> {
+        let grid = Grid::new(EXAMPLE)?;
+        assert_matches_grid(&JumpTable::new(&grid), &grid);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_and_remove_blocker() -> anyhow::Result<()> {
+        let grid = Grid::new(EXAMPLE)?;
+        let mut table = JumpTable::new(&grid);
+        let start = find_guard(&grid)?;
+        for obstruction in grid.points() {
+            if grid.get_point(obstruction) == BLOCKER || obstruction == start.0 {
+                continue;
+            }
+            let mut permuted_grid = grid.clone();
+            *permuted_grid.get_point_mut(obstruction) = BLOCKER;
+
+            table.add_blocker(obstruction);
+            assert_matches_grid(&table, &permuted_grid);
+            assert_eq!(
+                table.is_loop(start),
+                turtle_guard_and_bunny_guard(&permuted_grid, start).is_some()
+            );
+            table.remove_blocker(obstruction);
+        }
+        assert_matches_grid(&table, &grid);
+        Ok(())
+    }
+}
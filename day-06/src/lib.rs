@@ -1,2 +1,3 @@
+pub mod jump_table;
 pub mod part1;
 pub mod part2;
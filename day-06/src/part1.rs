@@ -1,51 +1,35 @@
 use std::collections::HashSet;
 
 use anyhow::{anyhow, Result};
-use aoclib::grid::{Grid, Point, DOWN, LEFT, RIGHT, UP};
-use rayon::{iter::ParallelBridge, prelude::*};
+use aoclib::{
+    direction::Direction,
+    grid::{Grid, Point},
+};
+use rayon::prelude::*;
+
+use crate::jump_table::JumpTable;
 
 pub const BLOCKER: u8 = b'#';
-pub const UP_MOVE: u8 = b'^';
-pub const RIGHT_MOVE: u8 = b'>';
-pub const LEFT_MOVE: u8 = b'<';
-pub const DOWN_MOVE: u8 = b'v';
-pub const GUARD: &[u8] = &[UP_MOVE, RIGHT_MOVE, LEFT_MOVE, DOWN_MOVE];
-
-pub fn turn_90(dir: u8) -> u8 {
-    match dir {
-        UP_MOVE => RIGHT_MOVE,
-        RIGHT_MOVE => DOWN_MOVE,
-        LEFT_MOVE => UP_MOVE,
-        DOWN_MOVE => LEFT_MOVE,
-        _ => unreachable!(),
-    }
-}
 
 /// Count the number of unique points the guard goes thorugh.
 ///
 /// When the guard hits an obstacle they turn right 90 and keep going.
 /// Stop when the guard leaves the grid.
-pub fn walk_the_guard(grid: Grid, start_pos: Point) -> usize {
+pub fn walk_the_guard(grid: Grid, (start_pos, start_dir): State) -> usize {
     let mut positions = HashSet::new();
     let mut pos = start_pos;
-    let mut dir = grid.get_point(pos);
+    let mut dir = start_dir;
 
     loop {
         positions.insert(pos);
-        let next_pos = match dir {
-            UP_MOVE => pos + UP,
-            RIGHT_MOVE => pos + RIGHT,
-            LEFT_MOVE => pos + LEFT,
-            DOWN_MOVE => pos + DOWN,
-            _ => unreachable!(),
-        };
+        let next_pos = pos + dir;
 
         if !grid.contains(next_pos) {
             break;
         }
 
         if grid.get_point(next_pos) == BLOCKER {
-            dir = turn_90(dir)
+            dir = dir.turn_right()
         } else {
             pos = next_pos
         }
@@ -54,72 +38,104 @@ pub fn walk_the_guard(grid: Grid, start_pos: Point) -> usize {
     positions.len()
 }
 
-#[inline]
-pub fn get_next_pos(pos: Point, dir: u8) -> Point {
-    match dir {
-        UP_MOVE => pos + UP,
-        RIGHT_MOVE => pos + RIGHT,
-        LEFT_MOVE => pos + LEFT,
-        DOWN_MOVE => pos + DOWN,
-        _ => unreachable!(),
-    }
-}
-
 /// Advance one step, turning counts as a step.
 ///
 /// If the step takes you out of the grid this returns None.
-pub fn one_step(grid: &Grid, pos: Point, dir: u8) -> Option<(Point, u8)> {
+pub fn one_step(grid: &Grid, pos: Point, dir: Direction) -> Option<(Point, Direction)> {
     let mut pos = pos;
     let mut dir = dir;
-    let next_pos = get_next_pos(pos, dir);
+    let next_pos = pos + dir;
 
     if !grid.contains(next_pos) {
         return None;
     }
 
     if grid.get_point(next_pos) == BLOCKER {
-        dir = turn_90(dir)
+        dir = dir.turn_right()
     } else {
         pos = next_pos
     }
     Some((pos, dir))
 }
 
+/// A guard's position and heading.
+pub type State = (Point, Direction);
+
+/// A loop the guard can never leave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first state on the guard's walk that is part of the loop.
+    pub entry: State,
+    /// The number of steps around the loop, where turning counts as a step.
+    pub length: usize,
+    /// Every state around the loop in walking order, starting with `entry`.
+    pub states: Vec<State>,
+}
+
 /// Floyd's tortoise and hare algorithm, with an adjustment for pathfinding.
-pub fn turtle_guard_and_bunny_guard(grid: Grid, start_pos: Point) -> bool {
-    let mut turtle_pos = start_pos;
-    let mut bunny_pos = start_pos;
-    let mut turtle_dir = grid.get_point(turtle_pos);
-    let mut bunny_dir = turtle_dir;
+///
+/// Returns the loop the guard gets stuck in starting from `start`, or None if they walk off
+/// the grid.
+pub fn turtle_guard_and_bunny_guard(grid: &Grid, start: State) -> Option<Cycle> {
+    let step = |(pos, dir): State| one_step(grid, pos, dir);
+    let mut turtle = start;
+    let mut bunny = start;
 
+    // Advance the turtle one step and the bunny two until they land on the same state
     loop {
-        // Advance turtle one step
-        if let Some((turtle_next_pos, turtle_next_dir)) = one_step(&grid, turtle_pos, turtle_dir) {
-            turtle_pos = turtle_next_pos;
-            turtle_dir = turtle_next_dir;
-        } else {
-            return false;
+        turtle = step(turtle)?;
+        bunny = step(step(bunny)?)?;
+        if bunny == turtle {
+            break;
         }
+    }
 
-        // Advance bunny two steps
-        if let Some((bunny_next_pos, bunny_next_dir)) = one_step(&grid, bunny_pos, bunny_dir) {
-            bunny_pos = bunny_next_pos;
-            bunny_dir = bunny_next_dir;
-        } else {
-            return false;
-        }
-        if let Some((bunny_next_pos, bunny_next_dir)) = one_step(&grid, bunny_pos, bunny_dir) {
-            bunny_pos = bunny_next_pos;
-            bunny_dir = bunny_next_dir;
-        } else {
-            return false;
-        }
+    // The meeting point is a multiple of the loop length from the start, so a turtle from the
+    // start and one from the meeting point meet at the entry
+    let mut entry = start;
+    while entry != turtle {
+        entry = step(entry)?;
+        turtle = step(turtle)?;
+    }
+
+    let mut states = vec![entry];
+    let mut state = step(entry)?;
+    while state != entry {
+        states.push(state);
+        state = step(state)?;
+    }
+    Some(Cycle {
+        entry,
+        length: states.len(),
+        states,
+    })
+}
 
-        // Check if they land on the same point
-        if bunny_pos == turtle_pos && bunny_dir == turtle_dir {
-            return true;
+/// Find where the guard starts and which way they're facing.
+pub fn find_guard(grid: &Grid) -> Result<State> {
+    grid.rows()
+        .flatten()
+        .find_map(|p| Some((p, Direction::from_arrow(grid.get_point(p))?)))
+        .ok_or(anyhow!("No start pos found for guard"))
+}
+
+/// Walk the guard's original route, and return each cell they walk onto for the first time,
+/// along with the state they were in just before stepping onto it.
+///
+/// An obstruction only changes the route if it's on it, and only from the first time the guard
+/// would reach it, so these are the only obstructions worth checking, and the guard's walk up
+/// to each of them doesn't need to be repeated.
+pub fn obstruction_candidates(grid: &Grid, start: State) -> Vec<(State, Point)> {
+    let mut visited = HashSet::from([start.0]);
+    let mut candidates = vec![];
+    let mut state = start;
+    while let Some(next) = one_step(grid, state.0, state.1) {
+        if next.0 != state.0 && visited.insert(next.0) {
+            candidates.push((state, next.0));
         }
+        state = next;
     }
+    candidates
 }
 
 /// Place obstacles in the grid and see if we can create a loop.
@@ -127,30 +143,39 @@ pub fn turtle_guard_and_bunny_guard(grid: Grid, start_pos: Point) -> bool {
 /// Count the number of points where placing an obstacle will create a loop
 pub fn obstruct_the_guard(input: &[u8]) -> Result<usize> {
     let grid = Grid::new(input)?;
-    let guard_start = grid
-        .rows()
-        .flatten()
-        .find(|p| GUARD.contains(&grid.get_point(*p)))
-        .ok_or(anyhow!("No start pos found for guard"))?;
-    let result = grid
-        .points()
-        .par_bridge()
-        .map(|point| {
-            if grid.get_point(point) != BLOCKER && point != guard_start {
-                // Try and make it a blocker
-                let mut permuted_grid = grid.clone();
-                *permuted_grid.get_point_mut(point) = BLOCKER;
-                // Test for loop
-                if turtle_guard_and_bunny_guard(permuted_grid, guard_start) {
-                    1
-                } else {
-                    0
-                }
-            } else {
-                0
-            }
+    let guard_start = find_guard(&grid)?;
+    let table = JumpTable::new(&grid);
+    let result = obstruction_candidates(&grid, guard_start)
+        .into_par_iter()
+        // One table per thread, and put each blocker back after checking it
+        .map_init(
+            || table.clone(),
+            |table, (resume_from, obstruction)| {
+                table.add_blocker(obstruction);
+                let looped = table.is_loop(resume_from);
+                table.remove_blocker(obstruction);
+                looped
+            },
+        )
+        .filter(|looped| *looped)
+        .count();
+    Ok(result)
+}
+
+/// Same as [`obstruct_the_guard`], but walking one cell at a time. Kept as a benchmark baseline.
+pub fn obstruct_the_guard_stepwise(input: &[u8]) -> Result<usize> {
+    let grid = Grid::new(input)?;
+    let guard_start = find_guard(&grid)?;
+    let result = obstruction_candidates(&grid, guard_start)
+        .into_par_iter()
+        .filter(|(resume_from, obstruction)| {
+            // Try and make it a blocker
+            let mut permuted_grid = grid.clone();
+            *permuted_grid.get_point_mut(*obstruction) = BLOCKER;
+            // Test for loop, picking up right before the guard reaches the blocker
+            turtle_guard_and_bunny_guard(&permuted_grid, *resume_from).is_some()
         })
-        .sum();
+        .count();
     Ok(result)
 }
 
@@ -158,11 +183,7 @@ pub fn obstruct_the_guard(input: &[u8]) -> Result<usize> {
 pub fn process(input: &[u8]) -> Result<String> {
     // Determine the number of unique points the guard visits
     let grid = Grid::new(input)?;
-    let start = grid
-        .rows()
-        .flatten()
-        .find(|p| GUARD.contains(&grid.get_point(*p)))
-        .ok_or(anyhow!("No start pos found for guard"))?;
+    let start = find_guard(&grid)?;
     let answer = walk_the_guard(grid, start);
 
     Ok(answer.to_string())
@@ -172,9 +193,7 @@ pub fn process(input: &[u8]) -> Result<String> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_process() -> anyhow::Result<()> {
-        let input = b"....#.....
+    const EXAMPLE: &[u8] = b"....#.....
 .........#
 ..........
 ..#.......
@@ -185,7 +204,73 @@ mod tests {
 #.........
 ......#...";
 
-        assert_eq!("41", process(input)?);
+    #[test]
+    fn test_process() -> anyhow::Result<()> {
+        assert_eq!("41", process(EXAMPLE)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_cycle() -> anyhow::Result<()> {
+        let grid = Grid::new(EXAMPLE)?;
+        assert_eq!(
+            turtle_guard_and_bunny_guard(&grid, find_guard(&grid)?),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle() -> anyhow::Result<()> {
+        let mut grid = Grid::new(EXAMPLE)?;
+        let start = find_guard(&grid)?;
+        // Just left of the guard, one of the puzzle's spots that traps them in a loop
+        *grid.get_point_mut(Point::new(3, 3)) = BLOCKER;
+        let cycle = turtle_guard_and_bunny_guard(&grid, start).unwrap();
+
+        // The loop comes back through where the guard started, so they're on it from the start
+        assert_eq!(cycle.entry, start);
+        assert_eq!(cycle.length, cycle.states.len());
+        assert_eq!(
+            cycle.states.iter().collect::<HashSet<_>>().len(),
+            cycle.length
+        );
+        for (i, (pos, dir)) in cycle.states.iter().enumerate() {
+            let next = cycle.states[(i + 1) % cycle.length];
+            assert_eq!(one_step(&grid, *pos, *dir), Some(next));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle_entry_off_the_loop() -> anyhow::Result<()> {
+        let grid = Grid::new(
+            b".#....
+.....#
+#.....
+....#.
+.^....",
+        )?;
+        let start = find_guard(&grid)?;
+        let cycle = turtle_guard_and_bunny_guard(&grid, start).unwrap();
+        // Two steps up to the loop, then around the rectangle between the blockers
+        assert_eq!(cycle.entry, (Point::new(1, 2), Direction::Up));
+        assert_eq!(cycle.length, 12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_resumed_checks_match_full_walks() -> anyhow::Result<()> {
+        let grid = Grid::new(EXAMPLE)?;
+        let start = find_guard(&grid)?;
+        for (resume_from, obstruction) in obstruction_candidates(&grid, start) {
+            let mut permuted_grid = grid.clone();
+            *permuted_grid.get_point_mut(obstruction) = BLOCKER;
+            assert_eq!(
+                turtle_guard_and_bunny_guard(&permuted_grid, resume_from).is_some(),
+                turtle_guard_and_bunny_guard(&permuted_grid, start).is_some()
+            );
+        }
         Ok(())
     }
 }
@@ -8,6 +8,7 @@ pub fn process(input: &[u8]) -> anyhow::Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::part1::obstruct_the_guard_stepwise;
 
     #[test]
     fn test_process() -> anyhow::Result<()> {
@@ -23,6 +24,7 @@ mod tests {
 ......#...";
 
         assert_eq!("6", process(input)?);
+        assert_eq!(6, obstruct_the_guard_stepwise(input)?);
         Ok(())
     }
 }
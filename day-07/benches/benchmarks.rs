@@ -14,3 +14,13 @@ fn part1() {
 fn part2() {
     part2::process(divan::black_box(include_bytes!("../input2.txt"))).unwrap();
 }
+
+#[divan::bench]
+fn part1_layers() {
+    part1::parse_input_layers(divan::black_box(include_bytes!("../input1.txt"))).unwrap();
+}
+
+#[divan::bench]
+fn part2_layers() {
+    part2::parse_input_layers(divan::black_box(include_bytes!("../input2.txt"))).unwrap();
+}
@@ -1,6 +1,8 @@
+use std::fs::File;
+
 use anyhow::Context;
 use clap::Parser;
-use day_07::part2::process;
+use day_07::part2::process_reader;
 
 #[derive(Parser, Debug)]
 #[command(name = "day_07")]
@@ -12,9 +14,12 @@ pub struct Args {
 #[tracing::instrument]
 fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
-    let _args = Args::parse();
+    let args = Args::parse();
 
-    let result = process(include_bytes!("../../input2.txt")).context("process part 2")?;
+    let input =
+        File::open(&args.input).with_context(|| format!("Failed to open {}", args.input))?;
+    // Stream the input rather than mapping it, since it's only ever read front to back
+    let result = process_reader(input).context("process part 2")?;
     println!("{}", result);
     Ok(())
 }
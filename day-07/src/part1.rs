@@ -1,6 +1,14 @@
-use anyhow::{anyhow, Result};
-use aoclib::{concat_u64, parsers::try_parse_num};
+use std::io::Read;
 
+use aoclib::{
+    digits::Digits,
+    input::Chunks,
+    parsers::{expect_bytes, expect_unsigned, ParseError},
+};
+
+use crate::solver::{solve, Operator};
+
+/// Every outcome of the equation so far, a layer per number.
 #[derive(Debug, Clone)]
 pub struct Checker {
     answer: u64,
@@ -37,34 +45,41 @@ impl Checker {
         for outcome in &self.layer {
             new_layer.push(outcome + number);
             new_layer.push(outcome * number);
-            new_layer.push(concat_u64(*outcome, number));
+            // Anything that overflows is already bigger than the answer
+            if let Some(concatenated) = outcome.checked_concat(number) {
+                new_layer.push(concatenated);
+            }
         }
         self.layer = new_layer;
     }
 
     pub fn check(&self) -> bool {
-        self.layer.iter().any(|value| *value == self.answer)
+        self.layer.contains(&self.answer)
     }
 }
 
-pub fn parse_input(input: &[u8]) -> Result<u64> {
+/// Sum the test values of the equations that `is_true` accepts, given the test value and the
+/// numbers.
+pub fn sum_calibrations(
+    input: &[u8],
+    mut is_true: impl FnMut(u64, &[u64]) -> bool,
+) -> Result<u64, ParseError> {
     let mut sum = 0;
     let mut offset = 0;
+    let mut numbers = vec![];
     while offset < input.len() {
         // parse a number up till :
-        let (answer, bytes_read) =
-            try_parse_num::<u64>(&input[offset..]).ok_or(anyhow!("Failed to parse num"))?;
-        // Consume a space and :
-        offset += 2 + bytes_read;
+        let (answer, end) = expect_unsigned::<u64>(input, offset, "a test value")?;
+        // Consume the : and a space
+        offset = expect_bytes(input, end, b": ")?;
 
-        let mut checker = Checker::new(answer);
+        numbers.clear();
         // parse <number>/s till a newline
         loop {
-            let (num, bytes_read) =
-                try_parse_num::<u64>(&input[offset..]).ok_or(anyhow!("Failed to parse num"))?;
-            offset += bytes_read;
+            let (num, end) = expect_unsigned::<u64>(input, offset, "a number")?;
+            offset = end;
 
-            checker.push(num);
+            numbers.push(num);
 
             // If we hit end of line, break loop
             // otherwise just increement offset to skip past space
@@ -75,7 +90,7 @@ pub fn parse_input(input: &[u8]) -> Result<u64> {
             offset += 1
         }
 
-        if checker.check() {
+        if is_true(answer, &numbers) {
             sum += answer;
         }
     }
@@ -83,14 +98,38 @@ pub fn parse_input(input: &[u8]) -> Result<u64> {
     Ok(sum)
 }
 
+pub fn parse_input(input: &[u8]) -> Result<u64, ParseError> {
+    sum_calibrations(input, |answer, numbers| {
+        solve(answer, numbers, Operator::PART1).is_some()
+    })
+}
+
+/// [`parse_input`] using [`Checker`], kept as a baseline for [`solve`].
+pub fn parse_input_layers(input: &[u8]) -> Result<u64, ParseError> {
+    sum_calibrations(input, |answer, numbers| {
+        let mut checker = Checker::new(answer);
+        for num in numbers {
+            checker.push(*num);
+        }
+        checker.check()
+    })
+}
+
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    parse_input(input).map(|x| x.to_string())
+    Ok(parse_input(input)?.to_string())
+}
+
+/// Same as [`process`], but reads the input in chunks so it never needs to fit in memory.
+#[tracing::instrument(skip(reader))]
+pub fn process_reader<R: Read>(reader: R) -> anyhow::Result<String> {
+    Ok(Chunks::new(reader).sum_with(parse_input)?.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_process() -> anyhow::Result<()> {
@@ -105,6 +144,37 @@ mod tests {
 292: 11 6 16 20";
 
         assert_eq!("3749", process(input)?);
+        assert_eq!(3749, parse_input_layers(input)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(16)]
+    #[case(1024)]
+    fn test_process_chunks(#[case] chunk_size: usize) -> anyhow::Result<()> {
+        let input = b"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+        let chunks = Chunks::with_chunk_size(&input[..], chunk_size);
+        assert_eq!(3749, chunks.sum_with(parse_input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_process_chunks_error_line() {
+        let input = b"190: 10 19\n3267: 81 40 27\n83: 17 5\n156 15 6\n";
+        let err = Chunks::with_chunk_size(&input[..], 4)
+            .sum_with(parse_input)
+            .unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (4, 4));
+    }
 }
@@ -1,51 +1,43 @@
-use anyhow::{anyhow, Result};
-use aoclib::parsers::try_parse_num;
+use std::io::Read;
 
-use crate::part1::Checker;
+use aoclib::input::Chunks;
+use aoclib::parsers::ParseError;
 
-pub fn parse_input(input: &[u8]) -> Result<u64> {
-    let mut sum = 0;
-    let mut offset = 0;
-    while offset < input.len() {
-        // parse a number up till :
-        let (answer, bytes_read) =
-            try_parse_num::<u64>(&input[offset..]).ok_or(anyhow!("Failed to parse num"))?;
-        // Consume a space and :
-        offset += 2 + bytes_read;
+use crate::part1::{sum_calibrations, Checker};
+use crate::solver::{solve, Operator};
 
-        let mut checker = Checker::new(answer);
-        // parse <number>/s till a newline
-        loop {
-            let (num, bytes_read) =
-                try_parse_num::<u64>(&input[offset..]).ok_or(anyhow!("Failed to parse num"))?;
-            offset += bytes_read;
-
-            checker.push_part2(num);
-
-            // If we hit end of line, break loop
-            // otherwise just increement offset to skip past space
-            if offset >= input.len() || input[offset] == b'\n' {
-                offset += 1;
-                break;
-            }
-            offset += 1
-        }
+pub fn parse_input(input: &[u8]) -> Result<u64, ParseError> {
+    sum_calibrations(input, |answer, numbers| {
+        solve(answer, numbers, Operator::PART2).is_some()
+    })
+}
 
-        if checker.check() {
-            sum += answer;
+/// [`parse_input`] using [`Checker`], kept as a baseline for [`solve`].
+pub fn parse_input_layers(input: &[u8]) -> Result<u64, ParseError> {
+    sum_calibrations(input, |answer, numbers| {
+        let mut checker = Checker::new(answer);
+        for num in numbers {
+            checker.push_part2(*num);
         }
-    }
-
-    Ok(sum)
+        checker.check()
+    })
 }
 
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    parse_input(input).map(|x| x.to_string())
+    Ok(parse_input(input)?.to_string())
+}
+
+/// Same as [`process`], but reads the input in chunks so it never needs to fit in memory.
+#[tracing::instrument(skip(reader))]
+pub fn process_reader<R: Read>(reader: R) -> anyhow::Result<String> {
+    Ok(Chunks::new(reader).sum_with(parse_input)?.to_string())
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_process() -> anyhow::Result<()> {
@@ -60,6 +52,27 @@ mod tests {
 292: 11 6 16 20";
 
         assert_eq!("11387", process(input)?);
+        assert_eq!(11387, parse_input_layers(input)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(16)]
+    #[case(1024)]
+    fn test_process_chunks(#[case] chunk_size: usize) -> anyhow::Result<()> {
+        let input = b"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+        let chunks = Chunks::with_chunk_size(&input[..], chunk_size);
+        assert_eq!(11387, chunks.sum_with(parse_input)?);
         Ok(())
     }
 }
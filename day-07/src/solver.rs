@@ -0,0 +1,210 @@
+use std::fmt;
+
+use aoclib::digits::Digits;
+
+/// An operator that can go between two numbers of an equation, always evaluated left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Add,
+    Multiply,
+    /// Join the digits of the left and right, `12 || 345 = 12345`.
+    Concat,
+}
+
+impl Operator {
+    pub const PART1: &'static [Operator] = &[Operator::Add, Operator::Multiply];
+    pub const PART2: &'static [Operator] = &[Operator::Add, Operator::Multiply, Operator::Concat];
+
+    /// `lhs op rhs`, or None if it overflows.
+    pub fn apply(self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Operator::Add => lhs.checked_add(rhs),
+            Operator::Multiply => lhs.checked_mul(rhs),
+            Operator::Concat => lhs.checked_concat(rhs),
+        }
+    }
+
+    /// The `lhs` that makes `lhs op rhs == result`, if there is one.
+    ///
+    /// Multiplying by zero has no single `lhs` to undo to, so it's left to [`solve`].
+    fn undo(self, result: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Operator::Add => result.checked_sub(rhs),
+            Operator::Multiply => (rhs != 0 && result.is_multiple_of(rhs)).then(|| result / rhs),
+            Operator::Concat => match u64::checked_pow10(rhs.count_digits()) {
+                Some(shift) => (result % shift == rhs).then_some(result / shift),
+                // rhs is as wide as a u64 gets, so there's no room for any lhs but zero
+                None => (result == rhs).then_some(0),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operator::Add => "+",
+            Operator::Multiply => "*",
+            Operator::Concat => "||",
+        })
+    }
+}
+
+/// Find operators from `operators` to put between `numbers` so they evaluate to `target`.
+///
+/// Works backwards from the target, undoing the last number with each operator that can
+/// produce it, so most branches are cut off after a step or two. Operators are tried in the
+/// order given and the first solution found is returned.
+pub fn solve(target: u64, numbers: &[u64], operators: &[Operator]) -> Option<Vec<Operator>> {
+    let mut solution = Vec::with_capacity(numbers.len().saturating_sub(1));
+    solve_backwards(target, numbers, operators, &mut solution).then(|| {
+        // Operators were found last first
+        solution.reverse();
+        solution
+    })
+}
+
+fn solve_backwards(
+    target: u64,
+    numbers: &[u64],
+    operators: &[Operator],
+    solution: &mut Vec<Operator>,
+) -> bool {
+    let Some((&last, rest)) = numbers.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return last == target;
+    }
+    for &op in operators {
+        if op == Operator::Multiply && last == 0 && target == 0 {
+            // Anything times zero is zero, so whatever comes before will do as long as it
+            // doesn't overflow on the way
+            let mut prefix = Vec::with_capacity(rest.len() - 1);
+            if evaluate_any(rest[0], &rest[1..], operators, &mut prefix) {
+                solution.push(op);
+                solution.extend(prefix.into_iter().rev());
+                return true;
+            }
+            continue;
+        }
+        let Some(lhs) = op.undo(target, last) else {
+            continue;
+        };
+        solution.push(op);
+        if solve_backwards(lhs, rest, operators, solution) {
+            return true;
+        }
+        solution.pop();
+    }
+    false
+}
+
+/// Find operators to put between `acc` and `numbers` that evaluate to anything at all, working
+/// forwards so every intermediate value is checked for overflow.
+fn evaluate_any(
+    acc: u64,
+    numbers: &[u64],
+    operators: &[Operator],
+    ops: &mut Vec<Operator>,
+) -> bool {
+    let Some((&next, rest)) = numbers.split_first() else {
+        return true;
+    };
+    for &op in operators {
+        let Some(acc) = op.apply(acc, next) else {
+            continue;
+        };
+        ops.push(op);
+        if evaluate_any(acc, rest, operators, ops) {
+            return true;
+        }
+        ops.pop();
+    }
+    false
+}
+
+/// Write out a solution from [`solve`] like `81 + 40 * 27`.
+pub fn render(numbers: &[u64], operators: &[Operator]) -> String {
+    let mut out = numbers.first().map(u64::to_string).unwrap_or_default();
+    for (op, number) in operators.iter().zip(&numbers[1..]) {
+        out.push_str(&format!(" {op} {number}"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::Checker;
+    use rstest::rstest;
+    use Operator::*;
+
+    fn evaluate(numbers: &[u64], operators: &[Operator]) -> Option<u64> {
+        operators
+            .iter()
+            .zip(&numbers[1..])
+            .try_fold(numbers[0], |acc, (op, n)| op.apply(acc, *n))
+    }
+
+    #[rstest]
+    #[case(190, &[10, 19], Operator::PART1, Some(vec![Multiply]))]
+    #[case(3267, &[81, 40, 27], Operator::PART1, Some(vec![Multiply, Add]))]
+    #[case(292, &[11, 6, 16, 20], Operator::PART1, Some(vec![Add, Multiply, Add]))]
+    #[case(156, &[15, 6], Operator::PART1, None)]
+    #[case(156, &[15, 6], Operator::PART2, Some(vec![Concat]))]
+    #[case(7290, &[6, 8, 6, 15], Operator::PART2, Some(vec![Multiply, Concat, Multiply]))]
+    #[case(192, &[17, 8, 14], Operator::PART2, Some(vec![Concat, Add]))]
+    #[case(21037, &[9, 7, 18, 13], Operator::PART2, None)]
+    #[case(5, &[5], Operator::PART1, Some(vec![]))]
+    #[case(5, &[], Operator::PART1, None)]
+    #[case(0, &[3, 4, 0], Operator::PART1, Some(vec![Add, Multiply]))]
+    #[case(0, &[u64::MAX, 2, 0], Operator::PART1, None)]
+    #[case(0, &[u64::MAX, 1, 0], Operator::PART1, Some(vec![Multiply, Multiply]))]
+    #[case(100, &[0, 100], Operator::PART2, Some(vec![Add]))]
+    fn test_solve(
+        #[case] target: u64,
+        #[case] numbers: &[u64],
+        #[case] operators: &[Operator],
+        #[case] expected: Option<Vec<Operator>>,
+    ) {
+        let solution = solve(target, numbers, operators);
+        if let Some(solution) = &solution {
+            assert_eq!(evaluate(numbers, solution), Some(target));
+        }
+        assert_eq!(solution, expected);
+    }
+
+    #[test]
+    fn test_only_given_operators() {
+        assert_eq!(solve(1234, &[12, 34], &[Concat]), Some(vec![Concat]));
+        assert_eq!(solve(46, &[12, 34], &[Concat, Multiply]), None);
+        assert_eq!(solve(46, &[12, 34], &[Concat, Add]), Some(vec![Add]));
+    }
+
+    #[test]
+    fn test_matches_checker() {
+        // Every target reachable from some small numbers, and the ones in between
+        let numbers = [2, 0, 11, 3, 1];
+        for target in 0..5000 {
+            let mut checker = Checker::new(target);
+            for n in numbers {
+                checker.push_part2(n);
+            }
+            assert_eq!(
+                solve(target, &numbers, Operator::PART2).is_some(),
+                checker.check(),
+                "{target}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(&[6, 8, 6, 15], &[Multiply, Concat, Multiply]),
+            "6 * 8 || 6 * 15"
+        );
+        assert_eq!(render(&[5], &[]), "5");
+    }
+}
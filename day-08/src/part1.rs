@@ -1,43 +1,38 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-use aoclib::grid::Grid;
+use aoclib::{counter::group_by_key, grid::Grid, lattice::Line};
 use itertools::Itertools;
 
-#[tracing::instrument]
-pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    let grid = Grid::new(input)?;
-    let mut antenae = HashMap::new();
-
-    for (letter, point) in grid
-        .points()
-        .map(|p| (grid.get_point(p), p))
-        .filter(|(kind, _point)| *kind != b'.')
-    {
-        antenae.entry(letter).or_insert(vec![]).push(point)
-    }
+/// Count the distinct points in line with two antennae of the same frequency.
+///
+/// With a `ratio`, only points where one of the antennae is `ratio` times as far away as the
+/// other count, otherwise every point on the line does.
+pub fn count_antinodes(grid: &Grid, ratio: Option<u64>) -> usize {
+    let antenae = group_by_key(grid.points().filter(|p| grid.get_point(*p) != b'.'), |p| {
+        grid.get_point(*p)
+    });
 
     // for each antenae type, go through all the pairs of two and find the focal points
     let mut positions = HashSet::new();
     for (_letter, locations) in antenae {
         for pair in locations.iter().combinations(2) {
-            let loc_a = pair[0];
-            let loc_b = pair[1];
-            let dx_dy_a = *loc_b - *loc_a;
-            let dx_dy_b = *loc_a - *loc_b;
-
-            let loc_a_1 = *loc_b + dx_dy_a;
-            let loc_b_1 = *loc_a + dx_dy_b;
-
-            if grid.contains(loc_a_1) {
-                positions.insert(loc_a_1);
-            }
-            if grid.contains(loc_b_1) {
-                positions.insert(loc_b_1);
+            let Some(line) = Line::through(*pair[0], *pair[1]) else {
+                continue;
+            };
+            match ratio {
+                Some(ratio) => positions.extend(line.points_at_ratio(grid, ratio)),
+                None => positions.extend(line.lattice_points(grid)),
             }
         }
     }
+    positions.len()
+}
 
-    Ok(positions.len().to_string())
+#[tracing::instrument]
+pub fn process(input: &[u8]) -> anyhow::Result<String> {
+    let grid = Grid::new(input)?;
+    // An antinode is where one antenna is twice as far away as the other
+    Ok(count_antinodes(&grid, Some(2)).to_string())
 }
 
 #[cfg(test)]
@@ -62,4 +57,16 @@ mod tests {
         assert_eq!("14", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_antinodes_between_antennae() -> anyhow::Result<()> {
+        // Antennae three apart also have antinodes a third of the way between them
+        let input = b"..........
+...a..a...
+..........";
+        let grid = Grid::new(input)?;
+        assert_eq!(count_antinodes(&grid, Some(2)), 4);
+        assert_eq!(count_antinodes(&grid, None), 10);
+        Ok(())
+    }
 }
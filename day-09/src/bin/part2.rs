@@ -1,5 +1,6 @@
-use day_09::part2::process;
+use day_09::part2::{checksums_by_placement, process};
 use anyhow::Context;
+use aoclib::input::map_file;
 use clap::Parser;
 
 #[derive(Parser, Debug)]
@@ -7,14 +8,23 @@ use clap::Parser;
 pub struct Args {
     #[clap(short, long, default_value = "input2.txt")]
     input: String,
+    /// Also print the checksum each placement strategy would give.
+    #[clap(long)]
+    compare_placements: bool,
 }
 
 #[tracing::instrument]
 fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
-    let _args = Args::parse();
+    let args = Args::parse();
 
-    let result = process(include_bytes!("../../input2.txt")).context("process part 2")?;
+    let input = map_file(&args.input)?;
+    let result = process(&input).context("process part 2")?;
     println!("{}", result);
+    if args.compare_placements {
+        for (placement, checksum) in checksums_by_placement(&input) {
+            println!("{placement:?}: {checksum}");
+        }
+    }
     Ok(())
 }
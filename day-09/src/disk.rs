@@ -0,0 +1,258 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// A run of blocks belonging to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileSpan {
+    pub id: usize,
+    pub start: usize,
+    pub len: usize,
+}
+
+impl FileSpan {
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.start + self.len
+    }
+
+    /// This span's part of the checksum, `id * (start + start + 1 + ... + end - 1)`.
+    #[inline]
+    pub fn checksum(&self) -> usize {
+        self.id * (self.len * self.start + self.len * self.len.saturating_sub(1) / 2)
+    }
+}
+
+/// A run of free blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreeSpan {
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Which free space a file moves into when more than one fits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Placement {
+    /// The leftmost space that fits, which is what the puzzle asks for.
+    FirstFit,
+    /// The smallest space that fits, leftmost on ties.
+    BestFit,
+    /// The largest space, leftmost on ties.
+    WorstFit,
+}
+
+impl Placement {
+    pub const ALL: [Placement; 3] = [Placement::FirstFit, Placement::BestFit, Placement::WorstFit];
+}
+
+/// Free spans bucketed by length, with a min-heap on start position per length.
+///
+/// Spans come from single digits in the disk map, so there are only a handful of buckets and
+/// finding a space for a file is a peek at each bucket's leftmost span.
+#[derive(Debug, Clone, Default)]
+pub struct FreeIndex {
+    by_len: Vec<BinaryHeap<Reverse<usize>>>,
+}
+
+impl FreeIndex {
+    /// Index free spans given as `(start, len)`.
+    pub fn new(spans: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut index = Self::default();
+        for (start, len) in spans {
+            index.insert(start, len);
+        }
+        index
+    }
+
+    pub fn insert(&mut self, start: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+        if self.by_len.len() <= len {
+            self.by_len.resize_with(len + 1, BinaryHeap::new);
+        }
+        self.by_len[len].push(Reverse(start));
+    }
+
+    /// Claim `len` blocks of a span that starts before `before`, returning where they start.
+    ///
+    /// Whatever is left of the span goes back in the index.
+    pub fn take(&mut self, len: usize, before: usize, placement: Placement) -> Option<usize> {
+        // The leftmost fitting span of each length, in increasing length
+        let mut candidates = (len..self.by_len.len()).filter_map(|span_len| {
+            let Reverse(start) = *self.by_len[span_len].peek()?;
+            (start < before).then_some((span_len, start))
+        });
+        let (span_len, start) = match placement {
+            Placement::FirstFit => candidates.min_by_key(|(_, start)| *start),
+            Placement::BestFit => candidates.next(),
+            Placement::WorstFit => candidates.next_back(),
+        }?;
+
+        self.by_len[span_len].pop();
+        self.insert(start + len, span_len - len);
+        Some(start)
+    }
+}
+
+/// A disk as runs of blocks rather than one entry per block.
+///
+/// A file can end up split over several spans with the same id after
+/// [`Disk::compact_blocks`]. `free` is the free space as parsed, and isn't kept up to date by
+/// compaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disk {
+    pub files: Vec<FileSpan>,
+    pub free: Vec<FreeSpan>,
+    /// Total number of blocks, free ones included.
+    pub len: usize,
+}
+
+impl Disk {
+    /// Parse a disk map of alternating file and free space lengths, ignoring anything that
+    /// isn't a digit, like a trailing newline.
+    pub fn parse(input: &[u8]) -> Self {
+        let mut disk = Self {
+            files: vec![],
+            free: vec![],
+            len: 0,
+        };
+        for (i, byte) in input.iter().filter(|b| b.is_ascii_digit()).enumerate() {
+            let len = (byte - b'0') as usize;
+            if i % 2 == 0 {
+                disk.files.push(FileSpan {
+                    id: i / 2,
+                    start: disk.len,
+                    len,
+                });
+            } else if len > 0 {
+                disk.free.push(FreeSpan {
+                    start: disk.len,
+                    len,
+                });
+            }
+            disk.len += len;
+        }
+        disk
+    }
+
+    pub fn checksum(&self) -> usize {
+        self.files.iter().map(FileSpan::checksum).sum()
+    }
+
+    /// Move blocks one at a time from the end of the disk into the leftmost free block, until
+    /// there are no gaps between files. Works a span at a time, splitting files as it goes.
+    pub fn compact_blocks(&mut self) {
+        let mut moved = vec![];
+        // The file being taken from the end of the disk, and how much of it is left in place
+        let mut back = self.files.len();
+        let mut remaining = 0;
+        'free: for free in &self.free {
+            let mut free = *free;
+            while free.len > 0 {
+                if remaining == 0 {
+                    if back == 0 {
+                        break 'free;
+                    }
+                    back -= 1;
+                    remaining = self.files[back].len;
+                    continue;
+                }
+                let file = self.files[back];
+                if free.start >= file.start {
+                    break 'free;
+                }
+                let len = free.len.min(remaining);
+                moved.push(FileSpan {
+                    id: file.id,
+                    start: free.start,
+                    len,
+                });
+                free.start += len;
+                free.len -= len;
+                remaining -= len;
+            }
+        }
+
+        // Whatever wasn't reached stays put, with the front of a partly moved file
+        if remaining > 0 {
+            self.files[back].len = remaining;
+            self.files.truncate(back + 1);
+        } else {
+            self.files.truncate(back);
+        }
+        self.files.extend(moved);
+        self.files.sort_unstable_by_key(|file| file.start);
+    }
+
+    /// Move whole files, highest id first, into free space to their left chosen by `placement`.
+    pub fn compact_files(&mut self, placement: Placement) {
+        let mut free_index = FreeIndex::new(self.free.iter().map(|free| (free.start, free.len)));
+        for file in self.files.iter_mut().rev() {
+            if let Some(start) = free_index.take(file.len, file.start, placement) {
+                file.start = start;
+            }
+        }
+        self.files.sort_unstable_by_key(|file| file.start);
+    }
+
+    /// Draw the disk like the puzzle does, with `.` for free blocks and each file's id.
+    ///
+    /// Ids past 9 only show their last digit, like the puzzle's examples never need more.
+    pub fn render(&self) -> String {
+        let mut blocks = vec![b'.'; self.len];
+        for file in &self.files {
+            blocks[file.start..file.end()].fill(b'0' + (file.id % 10) as u8);
+        }
+        String::from_utf8(blocks).expect("blocks are ascii")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::{checksum, expand, naive_compaction};
+    use rstest::rstest;
+
+    const EXAMPLE: &[u8] = b"2333133121414131402";
+
+    #[rstest]
+    #[case(b"12345", "0..111....22222")]
+    #[case(EXAMPLE, "00...111...2...333.44.5555.6666.777.888899")]
+    #[case(b"2333133121414131402\n", "00...111...2...333.44.5555.6666.777.888899")]
+    fn test_render(#[case] input: &[u8], #[case] expected: &str) {
+        assert_eq!(Disk::parse(input).render(), expected);
+    }
+
+    #[rstest]
+    #[case(b"12345", "022111222......")]
+    #[case(EXAMPLE, "0099811188827773336446555566..............")]
+    fn test_compact_blocks(#[case] input: &[u8], #[case] expected: &str) {
+        let mut disk = Disk::parse(input);
+        disk.compact_blocks();
+        assert_eq!(disk.render(), expected);
+    }
+
+    #[test]
+    fn test_compact_files() {
+        let mut disk = Disk::parse(EXAMPLE);
+        disk.compact_files(Placement::FirstFit);
+        assert_eq!(disk.render(), "00992111777.44.333....5555.6666.....8888..");
+        assert_eq!(disk.checksum(), 2858);
+    }
+
+    #[rstest]
+    #[case(EXAMPLE)]
+    #[case(b"12345")]
+    #[case(b"1313165")]
+    #[case(b"9953877292941")]
+    #[case(b"714892711")]
+    #[case(b"90909")]
+    fn test_matches_block_by_block(#[case] input: &[u8]) {
+        let mut mem = expand(input);
+        assert_eq!(Disk::parse(input).checksum(), checksum(&mem));
+
+        naive_compaction(&mut mem);
+        let mut disk = Disk::parse(input);
+        disk.compact_blocks();
+        assert_eq!(disk.checksum(), checksum(&mem));
+    }
+}
@@ -2,6 +2,8 @@ use std::iter;
 
 use itertools::Itertools;
 
+use crate::disk::Disk;
+
 #[derive(Debug, Copy, Clone)]
 pub enum MemoryBlock {
     Free,
@@ -36,7 +38,7 @@ pub fn expand(input: &[u8]) -> Vec<MemoryBlock> {
         } else {
             MemoryBlock::Free
         };
-        result.extend(iter::repeat(record).take(num as usize));
+        result.extend(iter::repeat_n(record, num as usize));
         is_file = !is_file;
     }
 
@@ -47,7 +49,9 @@ pub fn expand(input: &[u8]) -> Vec<MemoryBlock> {
 /// free space block (until there are no gaps remaining between file blocks).
 pub fn naive_compaction(mem: &mut [MemoryBlock]) {
     let mut lhs_offset = 0;
-    let mut rhs_offset = mem.len() - 1;
+    let Some(mut rhs_offset) = mem.len().checked_sub(1) else {
+        return;
+    };
 
     while lhs_offset < rhs_offset {
         // advance lhs_offset to the next free space
@@ -60,6 +64,11 @@ pub fn naive_compaction(mem: &mut [MemoryBlock]) {
             rhs_offset -= 1;
         }
 
+        // Every free block is already right of every file block
+        if lhs_offset >= rhs_offset {
+            break;
+        }
+
         // Swap the two elements
         mem.swap(lhs_offset, rhs_offset);
         lhs_offset += 1;
@@ -69,11 +78,12 @@ pub fn naive_compaction(mem: &mut [MemoryBlock]) {
 
 #[derive(Debug, Clone, Copy)]
 pub struct ContiguousBlock {
+    #[allow(dead_code)]
     kind: MemoryBlock,
     // inclusive
-    start: usize,
+    pub start: usize,
     // exclusive
-    end: usize,
+    pub end: usize,
 }
 
 impl ContiguousBlock {
@@ -86,8 +96,8 @@ impl ContiguousBlock {
     }
 }
 
-pub fn less_naive_compaction(mem: &mut [MemoryBlock]) {
-    // Buildup structure of free memory
+/// Split memory into runs of free space and files, both in left to right order.
+pub fn contiguous_blocks(mem: &[MemoryBlock]) -> (Vec<ContiguousBlock>, Vec<ContiguousBlock>) {
     let mut free_mem = vec![];
     let mut files = vec![];
     for (is_free, mut chunk) in &mem
@@ -122,6 +132,12 @@ pub fn less_naive_compaction(mem: &mut [MemoryBlock]) {
             }
         };
     }
+    (free_mem, files)
+}
+
+pub fn less_naive_compaction(mem: &mut [MemoryBlock]) {
+    // Buildup structure of free memory
+    let (mut free_mem, files) = contiguous_blocks(mem);
 
     // Go through files right to left
     for file in files.into_iter().rev() {
@@ -158,10 +174,9 @@ pub fn checksum(mem: &[MemoryBlock]) -> usize {
 
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    let mut expanded = expand(input);
-    naive_compaction(&mut expanded);
-    let checksum = checksum(&expanded);
-    Ok(checksum.to_string())
+    let mut disk = Disk::parse(input);
+    disk.compact_blocks();
+    Ok(disk.checksum().to_string())
 }
 
 #[cfg(test)]
@@ -175,4 +190,15 @@ mod tests {
         assert_eq!("1928", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_naive_compaction_without_free_space() {
+        // The scan for free space used to run off the end and swap out of bounds.
+        // For "90909" that's 0 * (0 + ... + 8) + 1 * (9 + ... + 17) + 2 * (18 + ... + 26)
+        for (input, expected) in [(b"9".as_slice(), 0), (b"90909", 117 + 2 * 198), (b"", 0)] {
+            let mut mem = expand(input);
+            naive_compaction(&mut mem);
+            assert_eq!(checksum(&mem), expected);
+        }
+    }
 }
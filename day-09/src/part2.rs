@@ -1,16 +1,31 @@
-use crate::part1::{checksum, expand, less_naive_compaction};
+use crate::disk::{Disk, Placement};
+
+/// The checksum after compacting with each placement strategy.
+pub fn checksums_by_placement(input: &[u8]) -> Vec<(Placement, usize)> {
+    let parsed = Disk::parse(input);
+    Placement::ALL
+        .into_iter()
+        .map(|placement| {
+            let mut disk = parsed.clone();
+            disk.compact_files(placement);
+            (placement, disk.checksum())
+        })
+        .collect()
+}
 
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    let mut expanded = expand(input);
-    less_naive_compaction(&mut expanded);
-    let checksum = checksum(&expanded);
-    Ok(checksum.to_string())
+    let mut disk = Disk::parse(input);
+    disk.compact_files(Placement::FirstFit);
+    Ok(disk.checksum().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::part1::{checksum, contiguous_blocks, expand, less_naive_compaction, MemoryBlock};
+    use rstest::rstest;
+    use std::cmp::Reverse;
 
     #[test]
     fn test_process() -> anyhow::Result<()> {
@@ -19,4 +34,71 @@ mod tests {
         assert_eq!("2858", process(input)?);
         Ok(())
     }
+
+    /// Scan every free span for every file.
+    fn reference_compaction(mem: &mut [MemoryBlock], placement: Placement) {
+        let (mut free_mem, files) = contiguous_blocks(mem);
+        for file in files.into_iter().rev() {
+            let fits = free_mem
+                .iter()
+                .enumerate()
+                .filter(|(_, free)| free.len() >= file.len() && free.start < file.start);
+            let chosen = match placement {
+                Placement::FirstFit => fits.min_by_key(|(_, free)| free.start),
+                Placement::BestFit => fits.min_by_key(|(_, free)| (free.len(), free.start)),
+                Placement::WorstFit => {
+                    fits.min_by_key(|(_, free)| (Reverse(free.len()), free.start))
+                }
+            };
+            let Some((i, _)) = chosen else {
+                continue;
+            };
+            for (file_index, free_index) in (file.start..file.end).zip(free_mem[i].start..) {
+                mem.swap(file_index, free_index);
+            }
+            free_mem[i].start += file.len();
+        }
+    }
+
+    #[test]
+    fn test_first_fit_matches_less_naive() {
+        let mut expected = expand(b"2333133121414131402");
+        less_naive_compaction(&mut expected);
+        let mut disk = Disk::parse(b"2333133121414131402");
+        disk.compact_files(Placement::FirstFit);
+        assert_eq!(disk.checksum(), checksum(&expected));
+    }
+
+    #[rstest]
+    #[case(b"2333133121414131402")]
+    #[case(b"12345")]
+    #[case(b"1313165")]
+    #[case(b"9953877292941")]
+    #[case(b"714892711")]
+    fn test_matches_reference(#[case] input: &[u8]) {
+        for placement in Placement::ALL {
+            let mut expected = expand(input);
+            reference_compaction(&mut expected, placement);
+            let mut disk = Disk::parse(input);
+            disk.compact_files(placement);
+            assert_eq!(disk.checksum(), checksum(&expected), "{placement:?}");
+        }
+    }
+
+    #[test]
+    fn test_placements_differ() {
+        // Free spans of 3, 1 and 2 blocks between four one block files
+        let checksums = checksums_by_placement(b"13111211");
+        assert_eq!(
+            checksums,
+            vec![
+                // 0321
+                (Placement::FirstFit, 3 + 2 * 2 + 3),
+                // 021..3
+                (Placement::BestFit, 2 + 2 + 5 * 3),
+                // 0321, after file 3 takes the 3 span and file 2 the rest of it
+                (Placement::WorstFit, 3 + 2 * 2 + 3),
+            ]
+        );
+    }
 }
@@ -20,3 +20,21 @@ fn part2() {
     ))
     .unwrap();
 }
+
+#[divan::bench]
+fn part1_bfs() {
+    let grid = aoclib::grid::Grid::new(divan::black_box(include_bytes!("../input1.txt"))).unwrap();
+    part1::part1_sum_trailheads(&grid);
+}
+
+#[divan::bench]
+fn part1_bfs_rayon() {
+    let grid = aoclib::grid::Grid::new(divan::black_box(include_bytes!("../input1.txt"))).unwrap();
+    part1::part1_sum_trailheads_rayon(&grid);
+}
+
+#[divan::bench]
+fn part2_bfs() {
+    let grid = aoclib::grid::Grid::new(divan::black_box(include_bytes!("../input2.txt"))).unwrap();
+    part1::part2_sum_trailheads(&grid);
+}
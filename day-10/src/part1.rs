@@ -4,6 +4,8 @@ use aoclib::grid::{Grid, Point, ORTHOGONAL};
 use rayon::iter::ParallelBridge;
 use rayon::prelude::*;
 
+use crate::trails::TrailMap;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct PointAndValue {
     point: Point,
@@ -27,7 +29,9 @@ pub fn ascii_to_num(value: u8) -> u8 {
     value - 48
 }
 
-/// Count the number of 9's accessable by this trailhead
+/// Count the number of 9's accessable by this trailhead.
+///
+/// Kept as a baseline for [`TrailMap`], which counts every trailhead in one pass.
 pub fn explore_trailhead(
     grid: &Grid,
     trailhead: Point,
@@ -69,7 +73,7 @@ pub fn part1_sum_trailheads_rayon(grid: &Grid) -> usize {
         .par_bridge()
         .map(|trailhead| {
             let mut stack = VecDeque::new();
-            let score = explore_trailhead(
+            explore_trailhead(
                 grid,
                 trailhead,
                 &mut stack,
@@ -78,8 +82,7 @@ pub fn part1_sum_trailheads_rayon(grid: &Grid) -> usize {
                  stack: &VecDeque<PointAndValue>| {
                     current_point.value + 1 == new_point.value && !stack.contains(&new_point)
                 },
-            );
-            score
+            )
         })
         .sum()
 }
@@ -129,7 +132,7 @@ pub fn part2_sum_trailheads(grid: &Grid) -> usize {
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
     let grid = Grid::new(input)?;
-    Ok(part1_sum_trailheads(&grid).to_string())
+    Ok(TrailMap::new(&grid).score_sum().to_string())
 }
 
 #[cfg(test)]
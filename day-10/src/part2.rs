@@ -1,11 +1,11 @@
 use aoclib::grid::Grid;
 
-use crate::part1::part2_sum_trailheads;
+use crate::trails::TrailMap;
 
 #[tracing::instrument]
 pub fn process(input: &[u8]) -> anyhow::Result<String> {
     let grid = Grid::new(input)?;
-    Ok(part2_sum_trailheads(&grid).to_string())
+    Ok(TrailMap::new(&grid).rating_sum().to_string())
 }
 
 #[cfg(test)]
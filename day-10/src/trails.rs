@@ -0,0 +1,142 @@
+use aoclib::grid::{Grid, Point, ORTHOGONAL};
+
+const SUMMIT: u8 = 9;
+
+/// The map's heights grouped by value, so trails can be counted a height at a time from the
+/// summits down rather than walked from every trailhead.
+#[derive(Debug, Clone)]
+pub struct TrailMap {
+    width: usize,
+    height: usize,
+    // None for anything that isn't a digit, which no trail can cross
+    heights: Vec<Option<u8>>,
+    // Cell indices at each height
+    by_height: [Vec<usize>; SUMMIT as usize + 1],
+}
+
+impl TrailMap {
+    pub fn new(grid: &Grid) -> Self {
+        let (width, height) = (grid.num_cols(), grid.num_rows());
+        let mut map = Self {
+            width,
+            height,
+            heights: vec![None; width * height],
+            by_height: Default::default(),
+        };
+        for point in grid.points() {
+            let byte = grid.get_point(point);
+            if byte.is_ascii_digit() {
+                let i = map.index(point);
+                map.heights[i] = Some(byte - b'0');
+                map.by_height[(byte - b'0') as usize].push(i);
+            }
+        }
+        map
+    }
+
+    /// The sum over trailheads of how many summits each can reach.
+    ///
+    /// Each cell gets a bitset of the summits reachable from it, the union of its uphill
+    /// neighbours' sets.
+    pub fn score_sum(&self) -> usize {
+        let words = self.by_height[SUMMIT as usize].len().div_ceil(64);
+        let mut reachable = vec![0u64; self.heights.len() * words];
+        for (summit, &i) in self.by_height[SUMMIT as usize].iter().enumerate() {
+            reachable[i * words + summit / 64] |= 1 << (summit % 64);
+        }
+        for height in (0..SUMMIT).rev() {
+            for &i in &self.by_height[height as usize] {
+                for next in self.uphill(i, height) {
+                    for word in 0..words {
+                        reachable[i * words + word] |= reachable[next * words + word];
+                    }
+                }
+            }
+        }
+        self.by_height[0]
+            .iter()
+            .map(|i| {
+                reachable[i * words..(i + 1) * words]
+                    .iter()
+                    .map(|word| word.count_ones() as usize)
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// The sum over trailheads of how many distinct trails start at each.
+    ///
+    /// Each cell's count is the sum of its uphill neighbours' counts.
+    pub fn rating_sum(&self) -> usize {
+        let mut paths = vec![0usize; self.heights.len()];
+        for &i in &self.by_height[SUMMIT as usize] {
+            paths[i] = 1;
+        }
+        for height in (0..SUMMIT).rev() {
+            for &i in &self.by_height[height as usize] {
+                paths[i] = self.uphill(i, height).map(|next| paths[next]).sum();
+            }
+        }
+        self.by_height[0].iter().map(|i| paths[*i]).sum()
+    }
+
+    /// Neighbours of the cell at `i` one step higher than `height`.
+    fn uphill(&self, i: usize, height: u8) -> impl Iterator<Item = usize> + '_ {
+        let point = Point::new((i % self.width) as isize, (i / self.width) as isize);
+        ORTHOGONAL
+            .into_iter()
+            .map(move |dir| point + dir)
+            .filter(|next| {
+                (0..self.width as isize).contains(&next.x)
+                    && (0..self.height as isize).contains(&next.y)
+            })
+            .map(|next| self.index(next))
+            .filter(move |next| self.heights[*next] == Some(height + 1))
+    }
+
+    #[inline]
+    fn index(&self, point: Point) -> usize {
+        point.y as usize * self.width + point.x as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::{part1_sum_trailheads, part2_sum_trailheads};
+    use rstest::rstest;
+
+    const EXAMPLE: &[u8] = b"89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+    #[rstest]
+    #[case(EXAMPLE, 36, 81)]
+    #[case(b"0123\n1234\n8765\n9876", 1, 16)]
+    #[case(b"...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9", 2, 2)]
+    #[case(
+        b"..90..9\n...1.98\n...2..7\n6543456\n765.987\n876....\n987....",
+        4,
+        13
+    )]
+    #[case(b"012345\n123456\n234567\n345678\n4.6789\n56789.", 2, 227)]
+    fn test_score_and_rating(#[case] input: &[u8], #[case] score: usize, #[case] rating: usize) {
+        let map = TrailMap::new(&Grid::new(input).unwrap());
+        assert_eq!(map.score_sum(), score);
+        assert_eq!(map.rating_sum(), rating);
+    }
+
+    #[test]
+    fn test_matches_bfs() -> anyhow::Result<()> {
+        let grid = Grid::new(EXAMPLE)?;
+        let map = TrailMap::new(&grid);
+        assert_eq!(map.score_sum(), part1_sum_trailheads(&grid));
+        assert_eq!(map.rating_sum(), part2_sum_trailheads(&grid));
+        Ok(())
+    }
+}
@@ -20,3 +20,8 @@ fn part2() {
     ))
     .unwrap();
 }
+
+#[divan::bench]
+fn part2_memo() {
+    part1::StoneCounter::new().count_all(divan::black_box(include_bytes!("../input2.txt")), 75);
+}
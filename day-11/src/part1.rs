@@ -1,5 +1,9 @@
-use aoclib::{count_digits, parsers::try_parse_num};
-use rustc_hash::{FxBuildHasher, FxHashMap};
+use aoclib::{
+    counter::Counter,
+    digits::Digits,
+    memo::{Memo, Recurse},
+    parsers::try_parse_num,
+};
 
 #[inline]
 pub fn blink(num: u64) -> (u64, Option<u64>) {
@@ -7,10 +11,9 @@ pub fn blink(num: u64) -> (u64, Option<u64>) {
         return (1, None);
     }
 
-    let digit_count = count_digits(num);
-    if digit_count % 2 == 0 {
-        let lhs = num / (10_u64.pow(digit_count / 2));
-        let rhs = num % (10_u64.pow(digit_count / 2));
+    let digit_count = num.count_digits();
+    if digit_count.is_multiple_of(2) {
+        let (lhs, rhs) = num.split_digits_at(digit_count / 2);
         return (lhs, Some(rhs));
     }
 
@@ -32,25 +35,66 @@ pub fn parse_stones(input: &[u8]) -> impl Iterator<Item = u64> + '_ {
 }
 
 pub fn stone_counter_acc(input: &[u8], blinks: usize) -> usize {
-    let mut stones = FxHashMap::default();
-    for num in parse_stones(input) {
-        stones.insert(num, 1);
-    }
+    let mut stones: Counter<u64> = parse_stones(input).collect();
 
     for _ in 0..blinks {
-        // let mut new_stones = FxHashMap::with_capacity(stones.len());
-        let mut new_stones =
-            FxHashMap::with_capacity_and_hasher(stones.len(), FxBuildHasher::default());
-        for (stone, count) in &stones {
+        let mut new_stones = Counter::with_capacity(stones.len());
+        for (stone, count) in stones.iter() {
             let (lhs, rhs) = blink(*stone);
-            *new_stones.entry(lhs).or_insert(0) += count;
+            new_stones.add_n(lhs, count);
             if let Some(rhs) = rhs {
-                *new_stones.entry(rhs).or_insert(0) += count;
+                new_stones.add_n(rhs, count);
             }
         }
         stones = new_stones;
     }
-    stones.values().copied().sum::<usize>()
+    stones.total()
+}
+
+type StoneKey = (u64, usize);
+type StoneFn = fn(&mut Recurse<'_, StoneKey, usize>, StoneKey) -> usize;
+
+/// How many stones `stone` becomes after `blinks`, written as plain recursion for [`Memo`].
+fn count_stones(recurse: &mut Recurse<'_, StoneKey, usize>, (stone, blinks): StoneKey) -> usize {
+    if blinks == 0 {
+        return 1;
+    }
+    match blink(stone) {
+        (lhs, None) => recurse((lhs, blinks - 1)),
+        (lhs, Some(rhs)) => recurse((lhs, blinks - 1)) + recurse((rhs, blinks - 1)),
+    }
+}
+
+/// Counts stones one starting stone at a time, sharing what it's learned between stones and
+/// blink counts.
+pub struct StoneCounter {
+    memo: Memo<StoneKey, usize, StoneFn>,
+}
+
+impl StoneCounter {
+    pub fn new() -> Self {
+        Self {
+            memo: Memo::new(count_stones),
+        }
+    }
+
+    /// How many stones `stone` becomes after `blinks`.
+    pub fn count(&mut self, stone: u64, blinks: usize) -> usize {
+        self.memo.get((stone, blinks))
+    }
+
+    /// How many stones the whole input becomes after `blinks`.
+    pub fn count_all(&mut self, input: &[u8], blinks: usize) -> usize {
+        parse_stones(input)
+            .map(|stone| self.count(stone, blinks))
+            .sum()
+    }
+}
+
+impl Default for StoneCounter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub fn stone_counter(input: &[u8], blinks: usize) -> usize {
@@ -103,4 +147,24 @@ mod tests {
     fn test_blink(#[case] input: u64, #[case] expected: (u64, Option<u64>)) {
         assert_eq!(blink(input), expected)
     }
+
+    #[rstest]
+    #[case(b"125 17", 6)]
+    #[case(b"125 17", 25)]
+    #[case(b"0 1 10 99 999", 1)]
+    #[case(b"0 1 10 99 999", 20)]
+    fn test_stone_counter_matches(#[case] input: &[u8], #[case] blinks: usize) {
+        let expected = stone_counter(input, blinks);
+        assert_eq!(StoneCounter::new().count_all(input, blinks), expected);
+        assert_eq!(stone_counter_acc(input, blinks), expected);
+    }
+
+    #[test]
+    fn test_stone_counter_per_stone() {
+        let mut counter = StoneCounter::new();
+        assert_eq!(counter.count(125, 6) + counter.count(17, 6), 22);
+        assert_eq!(counter.count(0, 0), 1);
+        assert_eq!(counter.count(0, 1), 1);
+        assert_eq!(counter.count(17, 1), 2);
+    }
 }
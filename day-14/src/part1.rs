@@ -1,18 +1,44 @@
-use anyhow::{bail, Result};
-use aoclib::parsers::try_parse_num;
+use aoclib::parsers::{expect_bytes, expect_signed, expect_unsigned, ParseError};
 
-// TEST DATA
-// pub const TALL: i16 = 7;
-// pub const WIDE: i16 = 11;
-pub const TALL: i16 = 103;
-pub const WIDE: i16 = 101;
+/// The size of the area the robots patrol, which they wrap around at the edges.
+///
+/// Both sides have to be positive, which [`Board::new`] checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Board {
+    pub width: i16,
+    pub height: i16,
+}
+
+impl Board {
+    /// The board in the puzzle examples.
+    pub const EXAMPLE: Board = Board::new(11, 7);
+    /// The board for the real puzzle input.
+    pub const REAL: Board = Board::new(101, 103);
 
-pub const QUAD_TOP_LEFT: Quadrant = Quadrant::new(0, WIDE / 2, 0, TALL / 2);
-pub const QUAD_TOP_RIGHT: Quadrant = Quadrant::new((WIDE / 2) + 1, WIDE, 0, TALL / 2);
-pub const QUAD_BOTTOM_LEFT: Quadrant = Quadrant::new(0, WIDE / 2, (TALL / 2) + 1, TALL);
-pub const QUAD_BOTTOM_RIGHT: Quadrant = Quadrant::new((WIDE / 2) + 1, WIDE, (TALL / 2) + 1, TALL);
+    /// # Panics
+    ///
+    /// If either side isn't positive, since positions wrap modulo the sides.
+    pub const fn new(width: i16, height: i16) -> Self {
+        assert!(width > 0 && height > 0, "board sides must be positive");
+        Self { width, height }
+    }
 
-#[derive(Debug)]
+    /// The four quadrants, in top left, top right, bottom left, bottom right order.
+    ///
+    /// The middle row and column (boards have odd sizes) aren't in any quadrant.
+    pub const fn quadrants(&self) -> [Quadrant; 4] {
+        let mid_x = self.width / 2;
+        let mid_y = self.height / 2;
+        [
+            Quadrant::new(0, mid_x, 0, mid_y),
+            Quadrant::new(mid_x + 1, self.width, 0, mid_y),
+            Quadrant::new(0, mid_x, mid_y + 1, self.height),
+            Quadrant::new(mid_x + 1, self.width, mid_y + 1, self.height),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Quadrant {
     pub x_lower: i16,
     pub x_upper: i16,
@@ -37,9 +63,13 @@ impl Quadrant {
     pub fn in_y(&self, y: i16) -> bool {
         (self.y_lower..self.y_upper).contains(&y)
     }
+
+    pub fn contains(&self, guard: &Guard) -> bool {
+        self.in_x(guard.x) && self.in_y(guard.y)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Guard {
     pub x: i16,
     pub y: i16,
@@ -56,96 +86,102 @@ impl Guard {
             y_velo,
         }
     }
+
+    /// Move one tick, wrapping around the edges of `board`.
+    pub fn step(&mut self, board: Board) {
+        self.x = (self.x + self.x_velo).rem_euclid(board.width);
+        self.y = (self.y + self.y_velo).rem_euclid(board.height);
+    }
+
+    /// Where the guard is after `ticks` ticks, without stepping through them.
+    ///
+    /// Each axis wraps, so only `ticks` modulo the side length matters.
+    pub fn position_at(&self, ticks: u64, board: Board) -> (i16, i16) {
+        let wrap = |position: i16, velocity: i16, side: i16| {
+            let ticks = (ticks % side as u64) as i64;
+            (position as i64 + velocity as i64 * ticks).rem_euclid(side as i64) as i16
+        };
+        (
+            wrap(self.x, self.x_velo, board.width),
+            wrap(self.y, self.y_velo, board.height),
+        )
+    }
+
+    /// Jump ahead `ticks` ticks.
+    pub fn advance(&mut self, ticks: u64, board: Board) {
+        (self.x, self.y) = self.position_at(ticks, board);
+    }
 }
 
-pub fn parse_guard(input: &[u8]) -> Result<((i16, i16, i16, i16), usize)> {
-    let mut offset = 2;
-    let Some((initial_x, bytes_read)) = try_parse_num::<u32>(&input[offset..]) else {
-        bail!("Failed to parse initial x pos.")
-    };
-    let initial_x = initial_x as i16;
-    offset += bytes_read + 1;
-
-    let Some((initial_y, bytes_read)) = try_parse_num::<u32>(&input[offset..]) else {
-        bail!("Failed to parse initial yj pos.")
-    };
-    let initial_y = initial_y as i16;
-    offset += bytes_read + 3;
-
-    let x_velo_neg = input[offset] == b'-';
-    if x_velo_neg {
-        offset += 1;
-    }
-    let Some((x_velocity, bytes_read)) = try_parse_num::<u32>(&input[offset..]) else {
-        bail!("Failed to parse x velocity.")
-    };
-    let x_velocity = x_velocity as i16 * if x_velo_neg { -1 } else { 1 };
-    offset += bytes_read + 1;
-
-    let y_velo_neg = input[offset] == b'-';
-    if y_velo_neg {
-        offset += 1;
-    }
-    let Some((y_velocity, bytes_read)) = try_parse_num::<u32>(&input[offset..]) else {
-        bail!("Failed to parse y velocity.")
-    };
-    let y_velocity = y_velocity as i16 * if y_velo_neg { -1 } else { 1 };
-    offset += bytes_read;
-
-    Ok(((initial_x, initial_y, x_velocity, y_velocity), offset))
+/// Where every guard is after `ticks` ticks, in the same order as `guards`.
+pub fn positions_at(
+    guards: &[Guard],
+    ticks: u64,
+    board: Board,
+) -> impl Iterator<Item = (i16, i16)> + '_ {
+    guards
+        .iter()
+        .map(move |guard| guard.position_at(ticks, board))
 }
 
-#[tracing::instrument]
-pub fn process(input: &[u8]) -> anyhow::Result<String> {
+/// Parse a `p=x,y v=dx,dy` line starting at `offset`.
+///
+/// Returns the guard, and the offset just past the line's last number.
+pub fn parse_guard(input: &[u8], offset: usize) -> Result<(Guard, usize), ParseError> {
+    let offset = expect_bytes(input, offset, b"p=")?;
+    let (initial_x, offset) = expect_unsigned::<i16>(input, offset, "an initial x position")?;
+    let offset = expect_bytes(input, offset, b",")?;
+    let (initial_y, offset) = expect_unsigned::<i16>(input, offset, "an initial y position")?;
+
+    let offset = expect_bytes(input, offset, b" v=")?;
+    let (x_velocity, offset) = expect_signed::<i16>(input, offset, "an x velocity")?;
+    let offset = expect_bytes(input, offset, b",")?;
+    let (y_velocity, offset) = expect_signed::<i16>(input, offset, "a y velocity")?;
+
+    Ok((
+        Guard::new(initial_x, initial_y, x_velocity, y_velocity),
+        offset,
+    ))
+}
+
+pub fn parse_guards(input: &[u8]) -> Result<Vec<Guard>, ParseError> {
     let mut offset = 0;
     let mut guards = vec![];
     while offset < input.len() {
-        let (pos, bytes_read) = parse_guard(&input[offset..])?;
-        let guard = Guard::new(pos.0, pos.1, pos.2, pos.3);
+        let (guard, end) = parse_guard(input, offset)?;
         guards.push(guard);
-        offset += bytes_read + 1;
+        offset = end + 1;
     }
+    Ok(guards)
+}
 
-    // ticks
-    for _ in 0..100 {
-        for guard in guards.iter_mut() {
-            let mut new_x = (guard.x + guard.x_velo) % WIDE;
-            let mut new_y = (guard.y + guard.y_velo) % TALL;
-            if new_x < 0 {
-                new_x += WIDE;
-            }
-            if new_y < 0 {
-                new_y += TALL;
-            }
-            guard.x = new_x;
-            guard.y = new_y;
-        }
-    }
+/// Multiply together the number of guards in each quadrant of `board`.
+pub fn safety_factor(guards: &[Guard], board: Board) -> usize {
+    board
+        .quadrants()
+        .iter()
+        .map(|quadrant| guards.iter().filter(|g| quadrant.contains(g)).count())
+        .product()
+}
 
-    // Count up guards in each quadrant
-    let mut top_left = 0;
-    let mut top_right = 0;
-    let mut bottom_left = 0;
-    let mut bottom_right = 0;
-
-    for guard in &guards {
-        if QUAD_TOP_LEFT.in_x(guard.x) && QUAD_TOP_LEFT.in_y(guard.y) {
-            top_left += 1;
-        } else if QUAD_TOP_RIGHT.in_x(guard.x) && QUAD_TOP_RIGHT.in_y(guard.y) {
-            top_right += 1;
-        } else if QUAD_BOTTOM_LEFT.in_x(guard.x) && QUAD_BOTTOM_LEFT.in_y(guard.y) {
-            bottom_left += 1;
-        } else if QUAD_BOTTOM_RIGHT.in_x(guard.x) && QUAD_BOTTOM_RIGHT.in_y(guard.y) {
-            bottom_right += 1;
-        }
+/// The safety factor after `ticks` ticks on `board`.
+pub fn simulate(input: &[u8], board: Board, ticks: u64) -> anyhow::Result<usize> {
+    let mut guards = parse_guards(input)?;
+    for guard in guards.iter_mut() {
+        guard.advance(ticks, board);
     }
+    Ok(safety_factor(&guards, board))
+}
 
-    Ok((top_left * top_right * bottom_left * bottom_right).to_string())
+#[tracing::instrument]
+pub fn process(input: &[u8]) -> anyhow::Result<String> {
+    Ok(simulate(input, Board::REAL, 100)?.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_process() -> anyhow::Result<()> {
@@ -162,7 +198,101 @@ p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3";
 
-        assert_eq!("12", process(input)?);
+        assert_eq!(12, simulate(input, Board::EXAMPLE, 100)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(5)]
+    #[case(100)]
+    #[case(10_403)]
+    #[case(12_345)]
+    fn test_position_at_matches_stepping(#[case] ticks: u64) -> anyhow::Result<()> {
+        let board = Board::REAL;
+        let guards = parse_guards(b"p=0,4 v=3,-3\np=6,3 v=-1,-3\np=100,102 v=-100,102")?;
+        let mut stepped = guards.clone();
+        for _ in 0..ticks {
+            for guard in stepped.iter_mut() {
+                guard.step(board);
+            }
+        }
+        let expected: Vec<_> = stepped.iter().map(|g| (g.x, g.y)).collect();
+        assert_eq!(
+            positions_at(&guards, ticks, board).collect::<Vec<_>>(),
+            expected
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_position_at_far_future() {
+        let guard = Guard::new(2, 4, 2, -3);
+        let board = Board::EXAMPLE;
+        // Everything repeats every 7 * 11 ticks
+        let far = 1_000_000_000_000;
+        assert_eq!(
+            guard.position_at(far, board),
+            guard.position_at(far % 77, board)
+        );
+        assert_eq!(guard.position_at(5, board), (1, 3));
+        assert_eq!(
+            guard.position_at(u64::MAX, board),
+            guard.position_at(u64::MAX % 77, board)
+        );
+    }
+
+    #[rstest]
+    #[case(Board::EXAMPLE, [
+        Quadrant::new(0, 5, 0, 3),
+        Quadrant::new(6, 11, 0, 3),
+        Quadrant::new(0, 5, 4, 7),
+        Quadrant::new(6, 11, 4, 7),
+    ])]
+    #[case(Board::REAL, [
+        Quadrant::new(0, 50, 0, 51),
+        Quadrant::new(51, 101, 0, 51),
+        Quadrant::new(0, 50, 52, 103),
+        Quadrant::new(51, 101, 52, 103),
+    ])]
+    fn test_quadrants(#[case] board: Board, #[case] expected: [Quadrant; 4]) {
+        assert_eq!(board.quadrants(), expected);
+    }
+
+    #[rstest]
+    #[case(0, 7)]
+    #[case(11, 0)]
+    #[case(-11, 7)]
+    #[should_panic(expected = "board sides must be positive")]
+    fn test_board_sides_must_be_positive(#[case] width: i16, #[case] height: i16) {
+        Board::new(width, height);
+    }
+
+    #[test]
+    fn test_process_real_board() -> anyhow::Result<()> {
+        // Two guards top left after 100 ticks on the 101x103 board, one in each other quadrant,
+        // and one on the middle row
+        let input = b"p=0,0 v=0,0
+p=100,0 v=0,0
+p=0,102 v=0,0
+p=0,60 v=1,0
+p=1,1 v=-1,-1
+p=7,51 v=3,0";
+
+        assert_eq!("2", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_parse_guard_error() {
+        let input = b"p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,x v=-1,2";
+        let err = process(input).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "expected an initial y position, found `x` at line 3, column 6\n    p=10,x v=-1,2\n         ^"
+        );
+    }
 }
@@ -1,64 +1,154 @@
-use std::i32;
+use aoclib::math::crt;
 
-use crate::part1::{
-    parse_guard, Guard, QUAD_BOTTOM_LEFT, QUAD_BOTTOM_RIGHT, QUAD_TOP_LEFT, QUAD_TOP_RIGHT, TALL,
-    WIDE,
-};
+use crate::part1::{parse_guards, Board, Guard};
 
-#[tracing::instrument]
-pub fn process(input: &[u8]) -> anyhow::Result<String> {
-    let mut offset = 0;
-    let mut guards = vec![];
-    while offset < input.len() {
-        let (pos, bytes_read) = parse_guard(&input[offset..])?;
-        let guard = Guard::new(pos.0, pos.1, pos.2, pos.3);
-        guards.push(guard);
-        offset += bytes_read + 1;
+/// Spread of one axis, as `n^2` times the variance so it stays an integer.
+fn spread(values: impl Iterator<Item = i16>) -> i64 {
+    let (mut n, mut sum, mut sum_squares) = (0_i64, 0_i64, 0_i64);
+    for value in values {
+        n += 1;
+        sum += value as i64;
+        sum_squares += (value as i64).pow(2);
     }
-    find_lowest_danger(guards);
-
-    Ok("XMAS".to_string())
+    n * sum_squares - sum * sum
 }
 
-pub fn find_lowest_danger(mut guards: Vec<Guard>) {
-    // ticks
-    let mut min_score = i32::MAX;
-    let mut second = 0;
-    for i in 0..11_000 {
-        // Count up guards in each quadrant
-        let mut top_left = 0;
-        let mut top_right = 0;
-        let mut bottom_left = 0;
-        let mut bottom_right = 0;
+/// Find the first tick where the guards draw the Christmas tree.
+///
+/// The x positions repeat every `board.width` ticks and the y positions every `board.height`
+/// ticks, so rather than checking every frame this finds the tick where each axis is most
+/// clumped together within its own period, and combines the two with the CRT. That's
+/// `width + height` frames instead of `width * height`.
+///
+/// Returns None if there are no guards to draw anything, or if the picture never lines up,
+/// which can only happen if the board's sides aren't coprime.
+pub fn find_tree(mut guards: Vec<Guard>, board: Board) -> Option<u64> {
+    if guards.is_empty() {
+        return None;
+    }
+    let mut best_x = (i64::MAX, 0);
+    let mut best_y = (i64::MAX, 0);
+    for tick in 0..board.width.max(board.height) as u64 {
+        if tick < board.width as u64 {
+            best_x = best_x.min((spread(guards.iter().map(|g| g.x)), tick));
+        }
+        if tick < board.height as u64 {
+            best_y = best_y.min((spread(guards.iter().map(|g| g.y)), tick));
+        }
         for guard in guards.iter_mut() {
-            let mut new_x = (guard.x + guard.x_velo) % WIDE;
-            let mut new_y = (guard.y + guard.y_velo) % TALL;
-            if new_x < 0 {
-                new_x += WIDE;
-            }
-            if new_y < 0 {
-                new_y += TALL;
-            }
-            guard.x = new_x;
-            guard.y = new_y;
+            guard.step(board);
+        }
+    }
+    crt([
+        (best_x.1, board.width as u64),
+        (best_y.1, board.height as u64),
+    ])
+    .map(|(tick, _)| tick)
+}
+
+/// Draw the guards after `ticks` ticks, with `#` for any tile with a guard on it.
+pub fn frame_at(mut guards: Vec<Guard>, board: Board, ticks: u64) -> String {
+    for guard in guards.iter_mut() {
+        guard.advance(ticks, board);
+    }
+
+    let width = board.width as usize + 1;
+    let mut frame = vec![b'.'; width * board.height as usize];
+    for row in frame.chunks_exact_mut(width) {
+        row[width - 1] = b'\n';
+    }
+    for guard in &guards {
+        frame[guard.y as usize * width + guard.x as usize] = b'#';
+    }
+    String::from_utf8(frame).expect("frame is ascii")
+}
 
-            // update score
-            if QUAD_TOP_LEFT.in_x(guard.x) && QUAD_TOP_LEFT.in_y(guard.y) {
-                top_left += 1;
-            } else if QUAD_TOP_RIGHT.in_x(guard.x) && QUAD_TOP_RIGHT.in_y(guard.y) {
-                top_right += 1;
-            } else if QUAD_BOTTOM_LEFT.in_x(guard.x) && QUAD_BOTTOM_LEFT.in_y(guard.y) {
-                bottom_left += 1;
-            } else if QUAD_BOTTOM_RIGHT.in_x(guard.x) && QUAD_BOTTOM_RIGHT.in_y(guard.y) {
-                bottom_right += 1;
+/// The first tick with the tree, and what the board looks like then.
+///
+/// Input without any guards is an error, since there's no tree to find.
+pub fn find_tree_frame(input: &[u8], board: Board) -> anyhow::Result<Option<(u64, String)>> {
+    let guards = parse_guards(input)?;
+    if guards.is_empty() {
+        anyhow::bail!("There are no guards to draw a tree");
+    }
+    let Some(tick) = find_tree(guards.clone(), board) else {
+        return Ok(None);
+    };
+    Ok(Some((tick, frame_at(guards, board, tick))))
+}
+
+#[tracing::instrument]
+pub fn process(input: &[u8]) -> anyhow::Result<String> {
+    let (tick, frame) = find_tree_frame(input, Board::REAL)?
+        .ok_or_else(|| anyhow::anyhow!("The guards never line up into a tree"))?;
+    tracing::debug!("Tree at tick {tick}:\n{frame}");
+    Ok(tick.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Guards that form a filled square with its corner at `(x, y)` on tick `tick`, and are
+    /// scattered everywhere else.
+    fn square_at(board: Board, x: i16, y: i16, tick: i64) -> Vec<Guard> {
+        // xorshift so the velocities are the same every run
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut velocity = |limit: i16| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % (2 * limit as u64 - 1)) as i16 - (limit - 1)
+        };
+        let mut guards = vec![];
+        for dy in 0..10 {
+            for dx in 0..10 {
+                let (x_velo, y_velo) = (velocity(board.width), velocity(board.height));
+                let start_x = (x + dx) as i64 - x_velo as i64 * tick;
+                let start_y = (y + dy) as i64 - y_velo as i64 * tick;
+                guards.push(Guard::new(
+                    start_x.rem_euclid(board.width as i64) as i16,
+                    start_y.rem_euclid(board.height as i64) as i16,
+                    x_velo,
+                    y_velo,
+                ));
             }
         }
-        let safety_score = top_left * top_right * bottom_left * bottom_right;
-        if safety_score < min_score {
-            min_score = safety_score;
-            second = i;
+        guards
+    }
+
+    #[test]
+    fn test_find_tree() {
+        let guards = square_at(Board::REAL, 40, 60, 7_338);
+        assert_eq!(find_tree(guards, Board::REAL), Some(7_338));
+    }
+
+    #[test]
+    fn test_no_guards() {
+        assert_eq!(find_tree(vec![], Board::REAL), None);
+        assert!(process(b"").is_err());
+        assert!(process(b"\n").is_err());
+    }
+
+    #[test]
+    fn test_frame_at() {
+        let guards = square_at(Board::REAL, 40, 60, 7_338);
+        let frame = frame_at(guards, Board::REAL, 7_338);
+        let rows: Vec<&str> = frame.lines().collect();
+        assert_eq!(rows.len(), 103);
+        assert!(rows.iter().all(|row| row.len() == 101));
+        for row in &rows[60..70] {
+            assert_eq!(&row[40..50], "##########");
         }
+        assert_eq!(frame.matches('#').count(), 100);
+    }
+
+    #[test]
+    fn test_frame_at_example() {
+        let guards = vec![Guard::new(2, 4, 2, -3)];
+        assert_eq!(
+            frame_at(guards, Board::EXAMPLE, 5),
+            "...........\n...........\n...........\n.#.........\n...........\n...........\n...........\n"
+        );
     }
-    println!("Score: {}", min_score);
-    println!("Time: {}", second + 1);
 }
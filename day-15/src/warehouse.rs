@@ -0,0 +1,405 @@
+use anyhow::{bail, Result};
+use aoclib::{
+    direction::Direction,
+    grid::{Grid, Point},
+};
+
+use crate::part1::{FLOOR, ROBOT, WALL};
+
+pub type ObjectId = usize;
+
+/// Something in the warehouse that can be pushed, made of cells that always move together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    /// Each cell of the object, and the byte it's drawn with.
+    pub cells: Vec<(Point, u8)>,
+}
+
+/// What happened on one move of the robot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveEvent {
+    pub direction: Direction,
+    /// Where the robot is after the move.
+    pub robot: Point,
+    /// The objects the robot pushed, not counting the robot itself.
+    pub displaced: Vec<ObjectId>,
+    /// Whether a wall stopped the robot, in which case nothing moved.
+    pub blocked: bool,
+}
+
+/// The warehouse floor, with the robot and everything it can push.
+///
+/// The robot is an object like any other, so a move is just the robot pushing itself. Pushing
+/// an object pushes every object in the way, in all four directions, and nothing moves at all
+/// if any of them would hit a wall.
+///
+/// Every robot move is recorded as a [`MoveEvent`], and can be undone in reverse order.
+#[derive(Debug, Clone)]
+pub struct Warehouse {
+    // Kept in sync with the objects, so it can be scored and printed
+    grid: Grid,
+    // The object on each cell, indexed by `y * width + x`
+    occupants: Vec<Option<ObjectId>>,
+    objects: Vec<Object>,
+    robot: ObjectId,
+    history: Vec<MoveEvent>,
+}
+
+impl Warehouse {
+    /// Find the robot and the pieces in `grid`.
+    ///
+    /// Each piece is the bytes of an object from left to right, like `b"O"` or `b"[]"`. Any
+    /// other byte that isn't a wall or floor is an error.
+    pub fn new(grid: Grid, pieces: &[&[u8]]) -> Result<Self> {
+        let mut warehouse = Self {
+            occupants: vec![None; grid.num_rows() * grid.num_cols()],
+            grid,
+            objects: vec![],
+            robot: usize::MAX,
+            history: vec![],
+        };
+
+        let points: Vec<Point> = warehouse.grid.points().collect();
+        for point in points {
+            let glyph = warehouse.grid.get_point(point);
+            if glyph == FLOOR || glyph == WALL || warehouse.occupant(point).is_some() {
+                continue;
+            }
+            if glyph == ROBOT {
+                warehouse.robot = warehouse.insert(vec![(point, ROBOT)]);
+                continue;
+            }
+            let Some(piece) = pieces.iter().find(|piece| warehouse.piece_at(point, piece)) else {
+                bail!("Unexpected `{}` at {:?}", glyph.escape_ascii(), point);
+            };
+            let cells = (0..piece.len())
+                .map(|i| (Point::new(point.x + i as isize, point.y), piece[i]))
+                .collect();
+            warehouse.insert(cells);
+        }
+
+        if warehouse.robot == usize::MAX {
+            bail!("No robot found");
+        }
+        Ok(warehouse)
+    }
+
+    /// Add an object of any shape. Every cell has to be on empty floor.
+    pub fn add_object(&mut self, cells: Vec<(Point, u8)>) -> Result<ObjectId> {
+        for &(cell, _) in &cells {
+            if !self.grid.contains(cell) || self.grid.get_point(cell) != FLOOR {
+                bail!("Can't place an object on {:?}", cell);
+            }
+        }
+        Ok(self.insert(cells))
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn object(&self, id: ObjectId) -> &Object {
+        &self.objects[id]
+    }
+
+    pub fn robot_id(&self) -> ObjectId {
+        self.robot
+    }
+
+    pub fn robot(&self) -> Point {
+        self.objects[self.robot].cells[0].0
+    }
+
+    /// The object covering `point`, if any.
+    pub fn occupant(&self, point: Point) -> Option<ObjectId> {
+        self.index(point).and_then(|i| self.occupants[i])
+    }
+
+    /// Every robot move so far, oldest first.
+    pub fn history(&self) -> &[MoveEvent] {
+        &self.history
+    }
+
+    /// Move the robot one step, pushing whatever is in the way.
+    pub fn move_robot(&mut self, direction: Direction) -> &MoveEvent {
+        let moved = self.push(self.robot, direction);
+        self.history.push(MoveEvent {
+            direction,
+            robot: self.robot(),
+            blocked: moved.is_empty(),
+            displaced: moved.into_iter().skip(1).collect(),
+        });
+        self.history.last().expect("just pushed")
+    }
+
+    /// Run a list of moves like `<^^>v`, skipping anything that isn't a move.
+    pub fn run(&mut self, moves: &[u8]) {
+        for direction in moves.iter().filter_map(|mv| Direction::from_arrow(*mv)) {
+            self.move_robot(direction);
+        }
+    }
+
+    /// Take back the last robot move, returning what it did.
+    ///
+    /// Objects moved with [`Warehouse::push`] aren't in the history, so undo them before
+    /// undoing any robot moves from before them.
+    pub fn undo(&mut self) -> Option<MoveEvent> {
+        let event = self.history.pop()?;
+        if !event.blocked {
+            let mut moved = vec![self.robot];
+            moved.extend(&event.displaced);
+            self.shift(&moved, event.direction.opposite());
+        }
+        Some(event)
+    }
+
+    /// Push object `id` one step, along with everything in its way.
+    ///
+    /// Returns the objects that moved, starting with `id`, or nothing if the push was blocked.
+    pub fn push(&mut self, id: ObjectId, direction: Direction) -> Vec<ObjectId> {
+        // Breadth first through everything touching the front of what's already moving
+        let mut moving = vec![id];
+        let mut i = 0;
+        while i < moving.len() {
+            for &(cell, _) in &self.objects[moving[i]].cells {
+                let target = cell + direction;
+                if !self.grid.contains(target) || self.grid.get_point(target) == WALL {
+                    return vec![];
+                }
+                match self.occupant(target) {
+                    Some(other) if !moving.contains(&other) => moving.push(other),
+                    _ => (),
+                }
+            }
+            i += 1;
+        }
+
+        self.shift(&moving, direction);
+        moving
+    }
+
+    /// Move objects without checking what's in the way.
+    fn shift(&mut self, ids: &[ObjectId], direction: Direction) {
+        // Lift everything before putting anything down, so objects can't overwrite each other
+        for &id in ids {
+            for i in 0..self.objects[id].cells.len() {
+                self.set(self.objects[id].cells[i].0, None, FLOOR);
+            }
+        }
+        for &id in ids {
+            for i in 0..self.objects[id].cells.len() {
+                let (cell, glyph) = &mut self.objects[id].cells[i];
+                *cell += direction;
+                let (cell, glyph) = (*cell, *glyph);
+                self.set(cell, Some(id), glyph);
+            }
+        }
+    }
+
+    fn insert(&mut self, cells: Vec<(Point, u8)>) -> ObjectId {
+        let id = self.objects.len();
+        for &(cell, glyph) in &cells {
+            self.set(cell, Some(id), glyph);
+        }
+        self.objects.push(Object { cells });
+        id
+    }
+
+    /// Whether `piece` is drawn starting at `point` on cells nothing else has claimed.
+    fn piece_at(&self, point: Point, piece: &[u8]) -> bool {
+        piece.iter().enumerate().all(|(i, glyph)| {
+            let cell = Point::new(point.x + i as isize, point.y);
+            self.grid.contains(cell)
+                && self.grid.get_point(cell) == *glyph
+                && self.occupant(cell).is_none()
+        })
+    }
+
+    fn set(&mut self, cell: Point, occupant: Option<ObjectId>, glyph: u8) {
+        if let Some(i) = self.index(cell) {
+            self.occupants[i] = occupant;
+        }
+        *self.grid.get_point_mut(cell) = glyph;
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        self.grid
+            .contains(point)
+            .then(|| point.y as usize * self.grid.num_cols() + point.x as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    use crate::part2::{expand_map, L_BOX, R_BOX};
+
+    fn wide(input: &[u8]) -> Result<Warehouse> {
+        Warehouse::new(Grid::new(&expand_map(input))?, &[&[L_BOX, R_BOX]])
+    }
+
+    #[test]
+    fn test_wide_example() -> Result<()> {
+        let mut warehouse = wide(
+            b"#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+",
+        )?;
+        warehouse.run(b"<vv<<^^<<^^");
+        assert_eq!(
+            warehouse.grid().get_data(),
+            b"##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_reports_moved() -> Result<()> {
+        let mut warehouse = wide(
+            b"#####
+#...#
+#.O.#
+#OO.#
+#.@.#
+#####
+",
+        )?;
+        let robot = warehouse.robot_id();
+        // Pushes the box right above, which pushes the box above that
+        let moved = warehouse.push(robot, Direction::Up);
+        assert_eq!(moved.len(), 3);
+        assert_eq!(moved[0], robot);
+        assert_eq!(warehouse.robot(), Point::new(4, 2));
+
+        // The top box is now against the wall
+        assert!(warehouse.push(robot, Direction::Up).is_empty());
+        assert_eq!(warehouse.robot(), Point::new(4, 2));
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(Direction::Up, Point::new(3, 4))]
+    #[case(Direction::Down, Point::new(3, 2))]
+    #[case(Direction::Left, Point::new(2, 3))]
+    #[case(Direction::Right, Point::new(4, 3))]
+    fn test_push_any_shape(#[case] direction: Direction, #[case] corner: Point) -> Result<()> {
+        let grid = Grid::new(
+            b"########
+#......#
+#......#
+#......#
+#......#
+#......#
+#@.....#
+########
+",
+        )?;
+        let mut warehouse = Warehouse::new(grid, &[])?;
+        // An L, with its corner at (3, 3)
+        let l = warehouse.add_object(vec![
+            (Point::new(3, 3), b'L'),
+            (Point::new(3, 4), b'L'),
+            (Point::new(4, 3), b'L'),
+        ])?;
+        assert_eq!(warehouse.push(l, direction), vec![l]);
+        assert_eq!(warehouse.occupant(corner), Some(l));
+        assert_eq!(warehouse.grid().get_point(corner), b'L');
+        assert_eq!(warehouse.object(l).cells[0].0, corner);
+        assert_eq!(
+            warehouse
+                .grid()
+                .get_data()
+                .iter()
+                .filter(|c| **c == b'L')
+                .count(),
+            3
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_piece() {
+        let grid = Grid::new(b"#####\n#@]O#\n#####\n").unwrap();
+        let err = Warehouse::new(grid, &[b"O"]).unwrap_err();
+        assert!(err.to_string().starts_with("Unexpected `]`"));
+    }
+
+    const SMALL_EXAMPLE: &[u8] = b"########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+";
+
+    #[test]
+    fn test_move_events() -> Result<()> {
+        let mut warehouse = Warehouse::new(Grid::new(SMALL_EXAMPLE)?, &[b"O"])?;
+        warehouse.run(b"<^^>>");
+        let summary: Vec<_> = warehouse
+            .history()
+            .iter()
+            .map(|event| (event.robot, event.displaced.len(), event.blocked))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Point::new(2, 5), 0, true),
+                (Point::new(2, 6), 0, false),
+                (Point::new(2, 6), 0, true),
+                (Point::new(3, 6), 1, false),
+                (Point::new(4, 6), 2, false),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo() -> Result<()> {
+        let moves = b"<^^>>>vv<v>>v<<";
+        let mut warehouse = Warehouse::new(Grid::new(SMALL_EXAMPLE)?, &[b"O"])?;
+        warehouse.run(moves);
+        assert_eq!(
+            warehouse.grid().get_data(),
+            b"########
+#....OO#
+##.....#
+#.....O#
+#.#O@..#
+#...O..#
+#...O..#
+########
+"
+        );
+
+        // Stepping back to any move matches running up to it
+        for step in (0..moves.len()).rev() {
+            assert_eq!(
+                warehouse.undo().map(|event| event.direction),
+                Direction::from_arrow(moves[step])
+            );
+            let mut replay = Warehouse::new(Grid::new(SMALL_EXAMPLE)?, &[b"O"])?;
+            replay.run(&moves[..step]);
+            assert_eq!(warehouse.grid().get_data(), replay.grid().get_data());
+        }
+        assert_eq!(warehouse.undo(), None);
+        assert_eq!(warehouse.grid().get_data(), SMALL_EXAMPLE);
+        Ok(())
+    }
+}
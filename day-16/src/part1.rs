@@ -1,21 +1,18 @@
 use std::collections::BinaryHeap;
 
+use anyhow::{Context, Result};
 use aoclib::grid::{Grid, Point, DOWN, LEFT, RIGHT, UP};
 use rustc_hash::FxHashMap;
 
-pub fn find_start(grid: &Grid) -> Point {
+pub fn find_start(grid: &Grid) -> Result<Point> {
     grid.points()
-        .filter(|p| grid.get_point(*p) == b'S')
-        .take(1)
-        .next()
-        .unwrap()
+        .find(|p| grid.get_point(*p) == b'S')
+        .context("No start `S` found in the maze")
 }
-pub fn find_end(grid: &Grid) -> Point {
+pub fn find_end(grid: &Grid) -> Result<Point> {
     grid.points()
-        .filter(|p| grid.get_point(*p) == b'E')
-        .take(1)
-        .next()
-        .unwrap()
+        .find(|p| grid.get_point(*p) == b'E')
+        .context("No end `E` found in the maze")
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -79,9 +76,9 @@ pub fn heuristic(start: Point, goal: Point, query: Point) -> usize {
 ///
 /// # References
 /// - https://www.redblobgames.com/pathfinding/a-star/introduction.html
-pub fn find_lowest_cost_path(grid: &mut Grid) -> usize {
-    let start = find_start(grid);
-    let goal = find_end(grid);
+pub fn find_lowest_cost_path(grid: &mut Grid) -> Result<usize> {
+    let start = find_start(grid)?;
+    let goal = find_end(grid)?;
 
     let mut frontier = BinaryHeap::new();
     frontier.push(Node::new(start, 0));
@@ -123,7 +120,10 @@ pub fn find_lowest_cost_path(grid: &mut Grid) -> usize {
     //     *grid.get_point_mut(node) = b'*';
     // }
 
-    *cost_so_far.get(&goal).unwrap()
+    cost_so_far
+        .get(&goal)
+        .copied()
+        .context("No path from start to end")
 }
 
 #[tracing::instrument]
@@ -131,7 +131,7 @@ pub fn process(input: &[u8]) -> anyhow::Result<String> {
     // Work through a maze from S to E, turns cost 1000, straight lines cost 1
     // Solve for lowest score
     let mut grid = Grid::new(input)?;
-    let answer = find_lowest_cost_path(&mut grid);
+    let answer = find_lowest_cost_path(&mut grid)?;
     Ok(answer.to_string())
 }
 
@@ -160,4 +160,13 @@ mod tests {
         assert_eq!("7036", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_missing_start() {
+        let input = b"#####
+#..E#
+#####";
+        let err = process(input).unwrap_err();
+        assert_eq!(err.to_string(), "No start `S` found in the maze");
+    }
 }
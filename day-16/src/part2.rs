@@ -1,21 +1,18 @@
 use std::collections::BinaryHeap;
 
+use anyhow::{Context, Result};
 use aoclib::grid::{Grid, Point, DOWN, LEFT, RIGHT, UP};
 use rustc_hash::{FxHashMap, FxHashSet};
 
-pub fn find_start(grid: &Grid) -> Point {
+pub fn find_start(grid: &Grid) -> Result<Point> {
     grid.points()
-        .filter(|p| grid.get_point(*p) == b'S')
-        .take(1)
-        .next()
-        .unwrap()
+        .find(|p| grid.get_point(*p) == b'S')
+        .context("No start `S` found in the maze")
 }
-pub fn find_end(grid: &Grid) -> Point {
+pub fn find_end(grid: &Grid) -> Result<Point> {
     grid.points()
-        .filter(|p| grid.get_point(*p) == b'E')
-        .take(1)
-        .next()
-        .unwrap()
+        .find(|p| grid.get_point(*p) == b'E')
+        .context("No end `E` found in the maze")
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -75,19 +72,20 @@ pub fn heuristic(start: Point, goal: Point, query: Point) -> usize {
     manhattan_dist(start, query) + manhattan_dist(goal, query)
 }
 
+/// The points on the found path, plus the came from and cost so far lookups of the search.
+pub type PathSearch = (
+    FxHashSet<Point>,
+    FxHashMap<Point, Point>,
+    FxHashMap<Point, usize>,
+);
+
 /// Ye Olde A*
 ///
 /// # References
 /// - https://www.redblobgames.com/pathfinding/a-star/introduction.html
-pub fn find_lowest_cost_path(
-    grid: &mut Grid,
-) -> (
-    FxHashSet<Point>,
-    FxHashMap<Point, Point>,
-    FxHashMap<Point, usize>,
-) {
-    let start = find_start(grid);
-    let goal = find_end(grid);
+pub fn find_lowest_cost_path(grid: &mut Grid) -> Result<PathSearch> {
+    let start = find_start(grid)?;
+    let goal = find_end(grid)?;
 
     let mut frontier = BinaryHeap::new();
     frontier.push(Node::new(start, 0));
@@ -130,7 +128,7 @@ pub fn find_lowest_cost_path(
         node = *came_from.get(&node).unwrap();
         // *grid.get_point_mut(node) = b'*';
     }
-    (path, came_from, cost_so_far)
+    Ok((path, came_from, cost_so_far))
 }
 
 /// Ye Olde A*
@@ -140,11 +138,11 @@ pub fn find_lowest_cost_path(
 pub fn find_lowest_cost_paths(
     grid: &mut Grid,
     original_path: FxHashSet<Point>,
-    original_came_from: FxHashMap<Point, Point>,
+    _original_came_from: FxHashMap<Point, Point>,
     original_cost_from: FxHashMap<Point, usize>,
-) -> usize {
-    let start = find_start(grid);
-    let goal = find_end(grid);
+) -> Result<usize> {
+    let start = find_start(grid)?;
+    let goal = find_end(grid)?;
 
     let mut frontier = BinaryHeap::new();
     frontier.push(Node::new(start, 0));
@@ -194,7 +192,7 @@ pub fn find_lowest_cost_paths(
     //     node = *came_from.get(&node).unwrap();
     //     *grid.get_point_mut(node) = b'*';
     // }
-    0
+    Ok(0)
 }
 
 #[tracing::instrument]
@@ -202,8 +200,8 @@ pub fn process(input: &[u8]) -> anyhow::Result<String> {
     // Work through a maze from S to E, turns cost 1000, straight lines cost 1
     // Solve for lowest score
     let mut grid = Grid::new(input)?;
-    let (x, y, z) = find_lowest_cost_path(&mut grid);
-    let answer = find_lowest_cost_paths(&mut grid, x, y, z);
+    let (x, y, z) = find_lowest_cost_path(&mut grid)?;
+    let answer = find_lowest_cost_paths(&mut grid, x, y, z)?;
     Ok(answer.to_string())
 }
 #[cfg(test)]